@@ -0,0 +1,488 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Deserialization of [`serde`] values from `application/x-www-form-urlencoded`.
+//!
+//! This module is only available if the `serde` Cargo feature is enabled.
+
+use crate::parse;
+use alloc::borrow::Cow;
+use alloc::collections::btree_map::{BTreeMap, Entry};
+use alloc::string::{String, ToString};
+use alloc::vec::{IntoIter, Vec};
+use core::fmt;
+use core::str::FromStr;
+use serde::de::value::{CowStrDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, IntoDeserializer, Visitor};
+
+/// Deserialize a struct, a map or a sequence of (name, value) pairs
+/// from a string in the `application/x-www-form-urlencoded` syntax.
+///
+/// This is the reverse of [`to_string`](crate::to_string):
+///
+/// * Names are matched with struct fields or become map keys.
+///   Values are parsed as the type of their field, such as a number.
+/// * Names that appear several times are deserialized as sequences,
+///   or as sequences with one element when they appear once.
+/// * Struct fields of type `Option` are `None` when their name is absent.
+///
+/// Names and values borrow from `input` when they contain no `+` or percent-encoded byte.
+///
+/// ```rust
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Search<'a> {
+///     q: String,
+///     #[serde(borrow)]
+///     tag: Vec<&'a str>,
+///     page: Option<u32>,
+/// }
+///
+/// let search: Search = form_urlencoded::from_str("q=fish+%26+chips&tag=food&tag=uk").unwrap();
+/// assert_eq!(
+///     search,
+///     Search { q: "fish & chips".into(), tag: vec!["food", "uk"], page: None }
+/// );
+/// ```
+pub fn from_str<'de, T: Deserialize<'de>>(input: &'de str) -> Result<T, Error> {
+    from_bytes(input.as_bytes())
+}
+
+/// Like [`from_str`], but for a byte string.
+pub fn from_bytes<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T, Error> {
+    T::deserialize(Deserializer { input })
+}
+
+/// Errors that can occur when deserializing a value with [`from_str`] or [`from_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The given name appears several times, but its value is not a sequence.
+    DuplicateKey(String),
+    /// The value for the given name could not be parsed as the expected type.
+    InvalidValue {
+        /// The name whose value is invalid.
+        name: String,
+        /// Why the value is invalid.
+        message: String,
+    },
+    /// The value for the given name is expected to be a map or a struct.
+    NestedMap(String),
+    /// An error reported by a `Deserialize` implementation, such as a missing field.
+    Custom(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DuplicateKey(name) => {
+                write!(
+                    f,
+                    "duplicate name `{}` for a value that is not a sequence",
+                    name
+                )
+            }
+            Error::InvalidValue { name, message } => {
+                write!(f, "invalid value for `{}`: {}", name, message)
+            }
+            Error::NestedMap(name) => {
+                write!(
+                    f,
+                    "cannot deserialize a map or struct from the value of `{}`",
+                    name
+                )
+            }
+            Error::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl de::StdError for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error::Custom(message.to_string())
+    }
+}
+
+/// Deserializes the whole input.
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(Groups::new(self.input))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let pairs = parse(self.input).map(|(name, value)| {
+            let value = Value {
+                text: value,
+                name: name.clone(),
+            };
+            (Value::name(name), value)
+        });
+        de::Deserializer::deserialize_seq(MapDeserializer::new(pairs), visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit_struct tuple_struct enum identifier ignored_any
+    }
+}
+
+/// The values of each name, in the order in which names first appear.
+struct Groups<'de> {
+    groups: IntoIter<(Cow<'de, str>, Vec<Cow<'de, str>>)>,
+    /// The values of the name returned by the last call to `next_key_seed`.
+    values: Option<Values<'de>>,
+}
+
+impl<'de> Groups<'de> {
+    fn new(input: &'de [u8]) -> Self {
+        let mut groups: Vec<(Cow<'de, str>, Vec<Cow<'de, str>>)> = Vec::new();
+        let mut indices: BTreeMap<Cow<'de, str>, usize> = BTreeMap::new();
+        for (name, value) in parse(input) {
+            match indices.entry(name) {
+                Entry::Occupied(entry) => groups[*entry.get()].1.push(value),
+                Entry::Vacant(entry) => {
+                    groups.push((entry.key().clone(), alloc::vec![value]));
+                    entry.insert(groups.len() - 1);
+                }
+            }
+        }
+        Groups {
+            groups: groups.into_iter(),
+            values: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Groups<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.groups.next() {
+            Some((name, values)) => {
+                self.values = Some(Values {
+                    name: name.clone(),
+                    values,
+                });
+                seed.deserialize(Value::name(name)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let values = self
+            .values
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(values)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.groups.len())
+    }
+}
+
+/// Implement `serde::Deserializer` methods by parsing the text with `FromStr`.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.$visit(self.parse()?)
+        }
+    )*};
+}
+
+/// A single name or value.
+struct Value<'de> {
+    text: Cow<'de, str>,
+    /// The name of this value, or the name itself, for errors.
+    name: Cow<'de, str>,
+}
+
+impl<'de> Value<'de> {
+    fn name(name: Cow<'de, str>) -> Self {
+        Value {
+            text: name.clone(),
+            name,
+        }
+    }
+
+    fn parse<T>(&self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.text
+            .parse()
+            .map_err(|error: T::Err| Error::InvalidValue {
+                name: self.name.clone().into_owned(),
+                message: error.to_string(),
+            })
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.text {
+            Cow::Borrowed(text) => visitor.visit_borrowed_str(text),
+            Cow::Owned(text) => visitor.visit_string(text),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.text {
+            Cow::Borrowed(text) => visitor.visit_borrowed_bytes(text.as_bytes()),
+            Cow::Owned(text) => visitor.visit_byte_buf(text.into_bytes()),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NestedMap(self.name.into_owned()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(CowStrDeserializer::new(self.text))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string seq tuple tuple_struct identifier
+    }
+}
+
+/// Implement `serde::Deserializer` methods by deserializing the only value.
+macro_rules! forward_to_single_value {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.single()?.$method(visitor)
+        }
+    )*};
+}
+
+/// All the values of a name.
+struct Values<'de> {
+    name: Cow<'de, str>,
+    /// Never empty.
+    values: Vec<Cow<'de, str>>,
+}
+
+impl<'de> Values<'de> {
+    fn single(mut self) -> Result<Value<'de>, Error> {
+        if self.values.len() > 1 {
+            return Err(Error::DuplicateKey(self.name.into_owned()));
+        }
+        Ok(Value {
+            text: self.values.pop().unwrap(),
+            name: self.name,
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Values<'de> {
+    type Error = Error;
+
+    forward_to_single_value! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let name = self.name;
+        let values = self.values.into_iter().map(move |text| Value {
+            text,
+            name: name.clone(),
+        });
+        de::Deserializer::deserialize_any(SeqDeserializer::new(values), visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NestedMap(self.name.into_owned()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
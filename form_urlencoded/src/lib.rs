@@ -12,11 +12,49 @@
 //!
 //! Converts between a string (such as an URL’s query string)
 //! and a sequence of (name, value) pairs.
+//!
+//! Names and values are UTF-8 by default.
+//! With the `encoding_rs` feature, [`parse_with_encoding`] and [`Serializer::encoding`]
+//! support the legacy character encodings of HTML documents.
+//!
+//! With the `serde` feature, [`to_string`] and [`from_str`] convert between
+//! this syntax and types that implement `Serialize` and `Deserialize`.
+//!
+//! [`parse_nested`] and [`Serializer::append_nested`] support names with brackets
+//! such as `user[emails][]`, which describe trees of [`FormValue`]s.
+//!
+#![cfg_attr(
+    feature = "encoding_rs",
+    doc = "[`parse_with_encoding`]: crate::parse_with_encoding"
+)]
+#![cfg_attr(
+    feature = "encoding_rs",
+    doc = "[`Serializer::encoding`]: crate::Serializer::encoding"
+)]
+#![cfg_attr(
+    not(feature = "encoding_rs"),
+    doc = "[`parse_with_encoding`]: \
+           https://docs.rs/form_urlencoded/1/form_urlencoded/fn.parse_with_encoding.html"
+)]
+#![cfg_attr(
+    not(feature = "encoding_rs"),
+    doc = "[`Serializer::encoding`]: \
+           https://docs.rs/form_urlencoded/1/form_urlencoded/struct.Serializer.html#method.encoding"
+)]
+#![cfg_attr(feature = "serde", doc = "[`to_string`]: crate::to_string")]
+#![cfg_attr(feature = "serde", doc = "[`from_str`]: crate::from_str")]
+#![cfg_attr(
+    not(feature = "serde"),
+    doc = "[`to_string`]: https://docs.rs/form_urlencoded/1/form_urlencoded/fn.to_string.html"
+)]
+#![cfg_attr(
+    not(feature = "serde"),
+    doc = "[`from_str`]: https://docs.rs/form_urlencoded/1/form_urlencoded/fn.from_str.html"
+)]
 #![no_std]
 
-// For forwards compatibility
 #[cfg(feature = "std")]
-extern crate std as _;
+extern crate std;
 
 extern crate alloc;
 
@@ -28,6 +66,24 @@ use alloc::string::String;
 use core::str;
 use percent_encoding::{percent_decode, percent_encode_byte};
 
+#[cfg(feature = "encoding_rs")]
+pub use encoding_rs;
+#[cfg(feature = "encoding_rs")]
+use encoding_rs::Encoding;
+
+#[cfg(feature = "serde")]
+pub mod de;
+mod nested;
+#[cfg(feature = "serde")]
+pub mod ser;
+
+pub use crate::nested::{parse_nested, FormMap, FormValue, NestedError, NestedParser};
+
+#[cfg(feature = "serde")]
+pub use crate::de::{from_bytes, from_str};
+#[cfg(feature = "serde")]
+pub use crate::ser::to_string;
+
 /// Convert a byte string in the `application/x-www-form-urlencoded` syntax
 /// into a iterator of (name, value) pairs.
 ///
@@ -37,12 +93,70 @@ use percent_encoding::{percent_decode, percent_encode_byte};
 /// converted to `[("#first", "%try%")]`.
 #[inline]
 pub fn parse(input: &[u8]) -> Parse<'_> {
-    Parse { input }
+    Parse {
+        input,
+        decoder: Decoder::Utf8,
+    }
+}
+
+/// Like [`parse`], but decode percent-decoded names and values with the given function
+/// instead of as UTF-8.
+///
+/// This is a legacy concept only relevant for HTML,
+/// where forms use the character encoding of their document.
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// fn latin1(bytes: &[u8]) -> Cow<'_, str> {
+///     bytes.iter().map(|&b| char::from(b)).collect()
+/// }
+///
+/// let pairs: Vec<_> = form_urlencoded::parse_with_decoder(b"caf%E9=cr%E8me", &latin1).collect();
+/// assert_eq!(pairs, [("café".into(), "crème".into())]);
+/// ```
+#[inline]
+pub fn parse_with_decoder<'a>(
+    input: &'a [u8],
+    decoder: &'a dyn Fn(&[u8]) -> Cow<'_, str>,
+) -> Parse<'a> {
+    Parse {
+        input,
+        decoder: Decoder::Override(decoder),
+    }
 }
+
+/// Like [`parse`], but decode percent-decoded names and values with the given encoding
+/// instead of UTF-8, as [`decode_legacy`] does.
+///
+/// ```rust
+/// use form_urlencoded::encoding_rs::SHIFT_JIS;
+///
+/// let pairs: Vec<_> = form_urlencoded::parse_with_encoding(b"q=%93%FA%96%7B", SHIFT_JIS).collect();
+/// assert_eq!(pairs, [("q".into(), "日本".into())]);
+/// ```
+#[cfg(feature = "encoding_rs")]
+#[inline]
+pub fn parse_with_encoding<'a>(input: &'a [u8], encoding: &'static Encoding) -> Parse<'a> {
+    Parse {
+        input,
+        decoder: Decoder::Encoding(encoding),
+    }
+}
+
 /// The return type of `parse()`.
 #[derive(Copy, Clone)]
 pub struct Parse<'a> {
     input: &'a [u8],
+    decoder: Decoder<'a>,
+}
+
+#[derive(Copy, Clone)]
+enum Decoder<'a> {
+    Utf8,
+    Override(&'a dyn Fn(&[u8]) -> Cow<'_, str>),
+    #[cfg(feature = "encoding_rs")]
+    Encoding(&'static Encoding),
 }
 
 impl<'a> Iterator for Parse<'a> {
@@ -62,17 +176,27 @@ impl<'a> Iterator for Parse<'a> {
             let mut split2 = sequence.splitn(2, |&b| b == b'=');
             let name = split2.next().unwrap();
             let value = split2.next().unwrap_or(&[][..]);
-            return Some((decode(name), decode(value)));
+            return Some((decode(name, self.decoder), decode(value, self.decoder)));
         }
     }
 }
 
-fn decode(input: &[u8]) -> Cow<'_, str> {
+fn decode<'a>(input: &'a [u8], decoder: Decoder<'_>) -> Cow<'a, str> {
     let replaced = replace_plus(input);
-    decode_utf8_lossy(match percent_decode(&replaced).into() {
+    let bytes = match percent_decode(&replaced).into() {
         Cow::Owned(vec) => Cow::Owned(vec),
         Cow::Borrowed(_) => replaced,
-    })
+    };
+    let decode_with = |f: &dyn Fn(&[u8]) -> Cow<'_, str>| match bytes {
+        Cow::Borrowed(bytes) => f(bytes),
+        Cow::Owned(ref bytes) => Cow::Owned(f(bytes).into_owned()),
+    };
+    match decoder {
+        Decoder::Utf8 => decode_utf8_lossy(bytes),
+        Decoder::Override(f) => decode_with(f),
+        #[cfg(feature = "encoding_rs")]
+        Decoder::Encoding(encoding) => decode_with(&|bytes| decode_legacy(encoding, bytes)),
+    }
 }
 
 /// Replace b'+' with b' '
@@ -176,7 +300,14 @@ impl<'a> Iterator for ByteSerialize<'a> {
 pub struct Serializer<'a, T: Target> {
     target: Option<T>,
     start_position: usize,
-    encoding: EncodingOverride<'a>,
+    encoding: Encoder<'a>,
+}
+
+#[derive(Copy, Clone)]
+enum Encoder<'a> {
+    Override(EncodingOverride<'a>),
+    #[cfg(feature = "encoding_rs")]
+    Encoding(&'static Encoding),
 }
 
 pub trait Target {
@@ -231,7 +362,7 @@ impl<'a, T: Target> Serializer<'a, T> {
         Serializer {
             target: Some(target),
             start_position,
-            encoding: None,
+            encoding: Encoder::Override(None),
         }
     }
 
@@ -245,7 +376,25 @@ impl<'a, T: Target> Serializer<'a, T> {
 
     /// Set the character encoding to be used for names and values before percent-encoding.
     pub fn encoding_override(&mut self, new: EncodingOverride<'a>) -> &mut Self {
-        self.encoding = new;
+        self.encoding = Encoder::Override(new);
+        self
+    }
+
+    /// Encode names and values with the given encoding before percent-encoding,
+    /// as [`encode_legacy`] does.
+    ///
+    /// ```rust
+    /// use form_urlencoded::encoding_rs::WINDOWS_1251;
+    ///
+    /// let encoded: String = form_urlencoded::Serializer::new(String::new())
+    ///     .encoding(WINDOWS_1251)
+    ///     .append_pair("q", "Мир 🌍")
+    ///     .finish();
+    /// assert_eq!(encoded, "q=%CC%E8%F0+%26%23127757%3B");
+    /// ```
+    #[cfg(feature = "encoding_rs")]
+    pub fn encoding(&mut self, encoding: &'static Encoding) -> &mut Self {
+        self.encoding = Encoder::Encoding(encoding);
         self
     }
 
@@ -329,6 +478,37 @@ impl<'a, T: Target> Serializer<'a, T> {
         self
     }
 
+    /// Serialize and append the fields of a struct, the entries of a map,
+    /// or a sequence of (name, value) pairs, as [`to_string`] does.
+    ///
+    /// If an error occurs, nothing is appended.
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// let map: BTreeMap<_, _> = [("lang", "fr"), ("q", "été")].iter().copied().collect();
+    /// let encoded: String = form_urlencoded::Serializer::new(String::new())
+    ///     .append_pair("v", "1")
+    ///     .extend_serialized(&map)
+    ///     .unwrap()
+    ///     .finish();
+    /// assert_eq!(encoded, "v=1&lang=fr&q=%C3%A9t%C3%A9");
+    /// ```
+    ///
+    /// Panics if called after `.finish()`.
+    #[cfg(feature = "serde")]
+    pub fn extend_serialized<S>(&mut self, value: &S) -> Result<&mut Self, ser::Error>
+    where
+        S: serde::Serialize + ?Sized,
+    {
+        let len = string(&mut self.target).len();
+        if let Err(error) = value.serialize(ser::PairsSerializer::new(self)) {
+            string(&mut self.target).truncate(len);
+            return Err(error);
+        }
+        Ok(self)
+    }
+
     /// If this serializer was constructed with a string, take and return that string.
     ///
     /// ```rust
@@ -365,7 +545,7 @@ fn string<T: Target>(target: &mut Option<T>) -> &mut String {
 fn append_pair(
     string: &mut String,
     start_position: usize,
-    encoding: EncodingOverride<'_>,
+    encoding: Encoder<'_>,
     name: &str,
     value: &str,
 ) {
@@ -375,25 +555,59 @@ fn append_pair(
     append_encoded(value, string, encoding);
 }
 
-fn append_key_only(
-    string: &mut String,
-    start_position: usize,
-    encoding: EncodingOverride,
-    name: &str,
-) {
+fn append_key_only(string: &mut String, start_position: usize, encoding: Encoder<'_>, name: &str) {
     append_separator_if_needed(string, start_position);
     append_encoded(name, string, encoding);
 }
 
-fn append_encoded(s: &str, string: &mut String, encoding: EncodingOverride<'_>) {
+fn append_encoded(s: &str, string: &mut String, encoding: Encoder<'_>) {
     string.extend(byte_serialize(&encode(encoding, s)))
 }
 
-pub(crate) fn encode<'a>(encoding_override: EncodingOverride<'_>, input: &'a str) -> Cow<'a, [u8]> {
-    if let Some(o) = encoding_override {
-        return o(input);
+fn encode<'a>(encoding: Encoder<'_>, input: &'a str) -> Cow<'a, [u8]> {
+    match encoding {
+        Encoder::Override(Some(o)) => o(input),
+        Encoder::Override(None) => input.as_bytes().into(),
+        #[cfg(feature = "encoding_rs")]
+        Encoder::Encoding(encoding) => encode_legacy(encoding, input),
     }
-    input.as_bytes().into()
+}
+
+/// Encode a name or value with the given encoding.
+///
+/// Characters that the encoding cannot represent are replaced with
+/// HTML numeric character references such as `&#128512;`, as browsers do.
+/// Like in HTML forms, UTF-16 encodings are replaced with UTF-8.
+///
+/// This can be used with [`Serializer::encoding_override`],
+/// or with the `encoding_override` option of the `url` crate’s parser:
+///
+/// ```rust
+/// use form_urlencoded::encoding_rs::SHIFT_JIS;
+///
+/// assert_eq!(form_urlencoded::encode_legacy(SHIFT_JIS, "日本"), &b"\x93\xFA\x96\x7B"[..]);
+/// assert_eq!(form_urlencoded::encode_legacy(SHIFT_JIS, "€"), &b"&#8364;"[..]);
+/// ```
+#[cfg(feature = "encoding_rs")]
+pub fn encode_legacy<'a>(encoding: &'static Encoding, input: &'a str) -> Cow<'a, [u8]> {
+    encoding.encode(input).0
+}
+
+/// Decode a percent-decoded name or value with the given encoding.
+///
+/// Malformed byte sequences are replaced with U+FFFD, the replacement character.
+/// A byte order mark is decoded like any other character.
+///
+/// This can be used with [`parse_with_decoder`], or `Url::query_pairs_with_encoding`.
+///
+/// ```rust
+/// use form_urlencoded::encoding_rs::WINDOWS_1251;
+///
+/// assert_eq!(form_urlencoded::decode_legacy(WINDOWS_1251, b"\xCC\xE8\xF0"), "Мир");
+/// ```
+#[cfg(feature = "encoding_rs")]
+pub fn decode_legacy<'a>(encoding: &'static Encoding, input: &'a [u8]) -> Cow<'a, str> {
+    encoding.decode_without_bom_handling(input).0
 }
 
 // std::ptr::addr_eq was stabilized in rust 1.76. Once we upgrade
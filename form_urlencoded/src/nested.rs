@@ -0,0 +1,359 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Nested names with brackets, such as `user[emails][]=a@example.com`,
+//! as used by PHP and Ruby on Rails.
+
+use crate::{parse, Serializer, Target};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The names and values of a form with nested names, as returned by [`parse_nested`].
+pub type FormMap = BTreeMap<String, FormValue>;
+
+/// A value of a form with nested names.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FormValue {
+    /// The value of a name without brackets, or ending with `[key]`.
+    Str(String),
+    /// The values of a name ending with `[]`.
+    List(Vec<FormValue>),
+    /// The values of names continuing with `[key]`.
+    Map(FormMap),
+}
+
+impl FormValue {
+    /// Return the string if this is a [`FormValue::Str`].
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FormValue::Str(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Return the elements if this is a [`FormValue::List`].
+    pub fn as_list(&self) -> Option<&[FormValue]> {
+        match self {
+            FormValue::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// Return the map if this is a [`FormValue::Map`].
+    pub fn as_map(&self) -> Option<&FormMap> {
+        match self {
+            FormValue::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a byte string in the `application/x-www-form-urlencoded` syntax
+/// with nested names, using the default limits of [`NestedParser`].
+///
+/// ```rust
+/// use form_urlencoded::FormValue;
+///
+/// let form = form_urlencoded::parse_nested(b"user[name]=Ann&user[tags][]=a&user[tags][]=b")?;
+/// let user = form["user"].as_map().unwrap();
+/// assert_eq!(user["name"], FormValue::Str("Ann".into()));
+/// assert_eq!(
+///     user["tags"],
+///     FormValue::List(vec![FormValue::Str("a".into()), FormValue::Str("b".into())])
+/// );
+/// # Ok::<(), form_urlencoded::NestedError>(())
+/// ```
+pub fn parse_nested(input: &[u8]) -> Result<FormMap, NestedError> {
+    NestedParser::new().parse(input)
+}
+
+/// A parser for nested names, with limits on the size of its input.
+///
+/// A name is split into a base name and bracketed keys: `a[b][]` is the key `b`
+/// in the map `a`, whose value is a list.
+///
+/// * Names that do not only consist of a base name followed by bracketed keys,
+///   such as `a[b` or `[a]`, are not nested.
+/// * A name without brackets, or ending with `[key]`, replaces the value of an earlier
+///   name, like the last value wins in PHP and Rails.
+///   So does a nested name when the earlier value has a different type.
+/// * In lists of maps, such as `items[][id]=1&items[][name]=a&items[][id]=2`,
+///   a key is added to the last map unless that map already has it,
+///   in which case a new map is started.
+///
+/// # Examples
+///
+/// ```rust
+/// use form_urlencoded::{NestedError, NestedParser};
+///
+/// let parser = NestedParser::new().max_depth(2).max_params(10);
+/// let form = parser.parse(b"items[][id]=1&items[][name]=a&items[][id]=2")?;
+/// assert_eq!(form["items"].as_list().unwrap().len(), 2);
+///
+/// assert_eq!(
+///     parser.parse(b"a[b][c][d]=1"),
+///     Err(NestedError::DepthLimitExceeded("a[b][c][d]".into()))
+/// );
+/// # Ok::<(), NestedError>(())
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NestedParser {
+    max_depth: usize,
+    max_params: usize,
+}
+
+impl Default for NestedParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NestedParser {
+    /// Create a parser that accepts up to 32 bracketed keys per name and 1000 pairs.
+    pub const fn new() -> Self {
+        NestedParser {
+            max_depth: 32,
+            max_params: 1000,
+        }
+    }
+
+    /// Set the maximum number of bracketed keys in a name.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the maximum number of (name, value) pairs.
+    pub fn max_params(mut self, max_params: usize) -> Self {
+        self.max_params = max_params;
+        self
+    }
+
+    /// Parse a byte string in the `application/x-www-form-urlencoded` syntax.
+    pub fn parse(&self, input: &[u8]) -> Result<FormMap, NestedError> {
+        self.parse_pairs(parse(input))
+    }
+
+    /// Build a tree from (name, value) pairs, such as those returned by
+    /// [`parse_with_decoder`](crate::parse_with_decoder).
+    pub fn parse_pairs<I, K, V>(&self, pairs: I) -> Result<FormMap, NestedError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut form = FormMap::new();
+        for (index, (name, value)) in pairs.into_iter().enumerate() {
+            if index == self.max_params {
+                return Err(NestedError::ParamsLimitExceeded);
+            }
+            let name = name.as_ref();
+            let (base, keys) = split_name(name, self.max_depth)?;
+            match keys.split_first() {
+                None => {
+                    form.insert(base.into(), FormValue::Str(value.into()));
+                }
+                Some((first, _)) => {
+                    let child = form.entry(base.into()).or_insert_with(|| first.container());
+                    insert(child, &keys, value.into());
+                }
+            }
+        }
+        Ok(form)
+    }
+}
+
+/// Errors that can occur when parsing nested names with a [`NestedParser`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NestedError {
+    /// The given name has more bracketed keys than the maximum depth.
+    DepthLimitExceeded(String),
+    /// The input has more (name, value) pairs than the maximum.
+    ParamsLimitExceeded,
+}
+
+impl fmt::Display for NestedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NestedError::DepthLimitExceeded(name) => {
+                write!(f, "too many nested keys in form name `{}`", name)
+            }
+            NestedError::ParamsLimitExceeded => f.write_str("too many form parameters"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NestedError {}
+
+/// A bracketed part of a name.
+#[derive(Clone, Copy)]
+enum Key<'a> {
+    /// `[key]`
+    Map(&'a str),
+    /// `[]`
+    List,
+}
+
+impl Key<'_> {
+    /// Return an empty value of the type this key looks up in.
+    fn container(self) -> FormValue {
+        match self {
+            Key::Map(_) => FormValue::Map(FormMap::new()),
+            Key::List => FormValue::List(Vec::new()),
+        }
+    }
+
+    fn fits(self, value: &FormValue) -> bool {
+        matches!(
+            (self, value),
+            (Key::Map(_), FormValue::Map(_)) | (Key::List, FormValue::List(_))
+        )
+    }
+}
+
+/// Split a name into its base name and bracketed keys.
+fn split_name(name: &str, max_depth: usize) -> Result<(&str, Vec<Key<'_>>), NestedError> {
+    let (base, mut rest) = match name.find('[') {
+        Some(open) if open > 0 => name.split_at(open),
+        _ => return Ok((name, Vec::new())),
+    };
+    let mut keys = Vec::new();
+    while !rest.is_empty() {
+        let close = match rest.strip_prefix('[').and_then(|rest| rest.find(']')) {
+            Some(close) => close + 1,
+            None => return Ok((name, Vec::new())),
+        };
+        if keys.len() == max_depth {
+            return Err(NestedError::DepthLimitExceeded(name.into()));
+        }
+        keys.push(match &rest[1..close] {
+            "" => Key::List,
+            key => Key::Map(key),
+        });
+        rest = &rest[close + 1..];
+    }
+    Ok((base, keys))
+}
+
+/// Set `value` at the path of `keys`, which is not empty, in `target`.
+fn insert(target: &mut FormValue, keys: &[Key<'_>], value: String) {
+    let (first, rest) = keys.split_first().unwrap();
+    if !first.fits(target) {
+        *target = first.container();
+    }
+    match (*first, target) {
+        (Key::Map(key), FormValue::Map(map)) => match rest.first() {
+            None => {
+                map.insert(key.into(), FormValue::Str(value));
+            }
+            Some(next) => insert(
+                map.entry(key.into()).or_insert_with(|| next.container()),
+                rest,
+                value,
+            ),
+        },
+        (Key::List, FormValue::List(list)) => match rest.first() {
+            None => list.push(FormValue::Str(value)),
+            Some(next) => {
+                let reuse_last = matches!(
+                    list.last(),
+                    Some(last) if next.fits(last) && !contains_path(last, rest)
+                );
+                if !reuse_last {
+                    list.push(next.container());
+                }
+                insert(list.last_mut().unwrap(), rest, value)
+            }
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Return whether a value is already set at the path of `keys` in `value`.
+fn contains_path(value: &FormValue, keys: &[Key<'_>]) -> bool {
+    match (value, keys.split_first()) {
+        (FormValue::Map(map), Some((Key::Map(key), rest))) => match map.get(*key) {
+            Some(child) => rest.is_empty() || contains_path(child, rest),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+impl<'a, T: Target> Serializer<'a, T> {
+    /// Serialize and append a value with nested names in bracket notation,
+    /// such that [`parse_nested`] returns it under `name`.
+    ///
+    /// Empty lists and maps are skipped.
+    /// Names are not escaped, so keys containing brackets do not round-trip.
+    /// Neither do lists of maps in general: when parsed, a map is merged into the one before it
+    /// unless that one already has its first key, in sorted order.
+    ///
+    /// ```rust
+    /// use form_urlencoded::FormValue;
+    ///
+    /// let form = form_urlencoded::parse_nested(b"user[name]=Ann&user[tags][]=a&user[tags][]=b")?;
+    /// let encoded = form_urlencoded::Serializer::new(String::new())
+    ///     .append_nested("user", &form["user"])
+    ///     .finish();
+    /// assert_eq!(
+    ///     encoded,
+    ///     "user%5Bname%5D=Ann&user%5Btags%5D%5B%5D=a&user%5Btags%5D%5B%5D=b"
+    /// );
+    /// # Ok::<(), form_urlencoded::NestedError>(())
+    /// ```
+    ///
+    /// Panics if called after `.finish()`.
+    pub fn append_nested(&mut self, name: &str, value: &FormValue) -> &mut Self {
+        let mut name = String::from(name);
+        self.append_nested_with(&mut name, value);
+        self
+    }
+
+    /// Serialize and append the values of a form with nested names,
+    /// such that [`parse_nested`] returns them.
+    ///
+    /// This simply calls `append_nested` for each entry.
+    ///
+    /// Panics if called after `.finish()`.
+    pub fn extend_nested(&mut self, form: &FormMap) -> &mut Self {
+        for (name, value) in form {
+            self.append_nested(name, value);
+        }
+        self
+    }
+
+    fn append_nested_with(&mut self, name: &mut String, value: &FormValue) {
+        let len = name.len();
+        match value {
+            FormValue::Str(value) => {
+                self.append_pair(name, value);
+            }
+            FormValue::List(list) => {
+                name.push_str("[]");
+                for value in list {
+                    self.append_nested_with(name, value);
+                }
+            }
+            FormValue::Map(map) => {
+                for (key, value) in map {
+                    name.push('[');
+                    name.push_str(key);
+                    name.push(']');
+                    self.append_nested_with(name, value);
+                    name.truncate(len);
+                }
+            }
+        }
+        name.truncate(len);
+    }
+}
@@ -0,0 +1,837 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization of [`serde`] values to `application/x-www-form-urlencoded`.
+//!
+//! This module is only available if the `serde` Cargo feature is enabled.
+
+use crate::{Serializer, Target};
+use alloc::string::{String, ToString};
+use core::fmt;
+use serde::ser::{self, Impossible, Serialize};
+
+/// Serialize a struct, a map or a sequence of (name, value) pairs
+/// in the `application/x-www-form-urlencoded` syntax.
+///
+/// * Struct fields and map entries become name/value pairs, in order.
+/// * Names and values can be strings, characters, booleans, numbers and unit enum variants.
+/// * Sequences become one pair per element, all with the same name.
+/// * `None` values are skipped, and unit values become names without `=`.
+///
+/// Values that cannot be represented this way, such as nested maps or structs,
+/// are reported as [`Error`]s.
+///
+/// ```rust
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Search<'a> {
+///     q: &'a str,
+///     tag: Vec<&'a str>,
+///     page: Option<u32>,
+/// }
+///
+/// let search = Search { q: "fish & chips", tag: vec!["food", "uk"], page: None };
+/// assert_eq!(
+///     form_urlencoded::to_string(&search).unwrap(),
+///     "q=fish+%26+chips&tag=food&tag=uk"
+/// );
+/// ```
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut serializer = Serializer::new(String::new());
+    serializer.extend_serialized(value)?;
+    Ok(serializer.finish())
+}
+
+/// Errors that can occur when serializing a value with [`to_string`]
+/// or [`Serializer::extend_serialized`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value is not a struct, a map or a sequence of (name, value) pairs.
+    TopLevel,
+    /// The value for the given name is a map or a struct.
+    NestedMap(String),
+    /// The value for the given name is a sequence containing sequences.
+    NestedSequence(String),
+    /// A name or value of the described kind cannot be serialized, such as bytes.
+    Unsupported(&'static str),
+    /// An error reported by a `Serialize` implementation.
+    Custom(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TopLevel => f.write_str(
+                "form data must be a struct, a map or a sequence of (name, value) pairs",
+            ),
+            Error::NestedMap(name) => {
+                write!(
+                    f,
+                    "cannot serialize a map or struct as the value of `{}`",
+                    name
+                )
+            }
+            Error::NestedSequence(name) => write!(
+                f,
+                "cannot serialize a sequence inside the sequence value of `{}`",
+                name
+            ),
+            Error::Unsupported(what) => write!(f, "cannot serialize {} as form data", what),
+            Error::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl ser::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error::Custom(message.to_string())
+    }
+}
+
+const NOT_A_PAIR: &str = "a sequence element other than a (name, value) pair";
+const INVALID_NAME: &str = "a name other than a string, a number or a unit variant";
+const VARIANT_WITH_DATA: &str = "an enum variant with data";
+
+/// Implement `serde::Serializer` methods for the given scalar types by returning an error.
+macro_rules! reject {
+    ($error:expr; $($method:ident($ty:ty))*) => {$(
+        fn $method(self, _: $ty) -> Result<Self::Ok, Error> {
+            Err($error)
+        }
+    )*};
+}
+
+macro_rules! reject_scalars {
+    ($error:expr) => {
+        reject! {
+            $error;
+            serialize_bool(bool)
+            serialize_i8(i8)
+            serialize_i16(i16)
+            serialize_i32(i32)
+            serialize_i64(i64)
+            serialize_i128(i128)
+            serialize_u8(u8)
+            serialize_u16(u16)
+            serialize_u32(u32)
+            serialize_u64(u64)
+            serialize_u128(u128)
+            serialize_f32(f32)
+            serialize_f64(f64)
+            serialize_char(char)
+            serialize_str(&str)
+            serialize_bytes(&[u8])
+        }
+    };
+}
+
+/// Implement `serde::Serializer` methods for the given scalar types with `serialize_str`.
+macro_rules! serialize_to_string {
+    ($($method:ident($ty:ty))*) => {$(
+        fn $method(self, value: $ty) -> Result<Self::Ok, Error> {
+            self.serialize_str(&value.to_string())
+        }
+    )*};
+}
+
+macro_rules! serialize_scalars_to_string {
+    () => {
+        serialize_to_string! {
+            serialize_bool(bool)
+            serialize_i8(i8)
+            serialize_i16(i16)
+            serialize_i32(i32)
+            serialize_i64(i64)
+            serialize_i128(i128)
+            serialize_u8(u8)
+            serialize_u16(u16)
+            serialize_u32(u32)
+            serialize_u64(u64)
+            serialize_u128(u128)
+            serialize_f32(f32)
+            serialize_f64(f64)
+            serialize_char(char)
+        }
+    };
+}
+
+/// Serializes the top-level value of [`Serializer::extend_serialized`].
+pub(crate) struct PairsSerializer<'s, 'a, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+}
+
+impl<'s, 'a, T: Target> PairsSerializer<'s, 'a, T> {
+    pub(crate) fn new(form: &'s mut Serializer<'a, T>) -> Self {
+        PairsSerializer { form }
+    }
+}
+
+impl<'s, 'a, T: Target> ser::Serializer for PairsSerializer<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Pairs<'s, 'a, T>;
+    type SerializeTuple = Pairs<'s, 'a, T>;
+    type SerializeTupleStruct = Pairs<'s, 'a, T>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Pairs<'s, 'a, T>;
+    type SerializeStruct = Pairs<'s, 'a, T>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    reject_scalars!(Error::TopLevel);
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, value: &V) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        Err(Error::TopLevel)
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &V,
+    ) -> Result<(), Error> {
+        Err(Error::TopLevel)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(Pairs::new(self.form))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Ok(Pairs::new(self.form))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Ok(Pairs::new(self.form))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::TopLevel)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(Pairs::new(self.form))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(Pairs::new(self.form))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::TopLevel)
+    }
+}
+
+/// The fields of a struct, the entries of a map, or a sequence of (name, value) pairs.
+pub(crate) struct Pairs<'s, 'a, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+    /// The name of the map entry whose value is serialized next.
+    name: Option<String>,
+}
+
+impl<'s, 'a, T: Target> Pairs<'s, 'a, T> {
+    fn new(form: &'s mut Serializer<'a, T>) -> Self {
+        Pairs { form, name: None }
+    }
+
+    fn serialize_pair<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(PairSerializer {
+            form: &mut *self.form,
+        })
+    }
+}
+
+impl<'s, 'a, T: Target> ser::SerializeSeq for Pairs<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_pair(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, T: Target> ser::SerializeTuple for Pairs<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_pair(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, T: Target> ser::SerializeTupleStruct for Pairs<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_pair(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, T: Target> ser::SerializeMap for Pairs<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<K: Serialize + ?Sized>(&mut self, key: &K) -> Result<(), Error> {
+        self.name = Some(key.serialize(NameSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        let name = self
+            .name
+            .take()
+            .expect("serialize_value called before serialize_key");
+        value.serialize(ValueSerializer {
+            form: &mut *self.form,
+            name: &name,
+            in_sequence: false,
+        })
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, T: Target> ser::SerializeStruct for Pairs<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(ValueSerializer {
+            form: &mut *self.form,
+            name,
+            in_sequence: false,
+        })
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes an element of a top-level sequence, which must be a (name, value) pair.
+struct PairSerializer<'s, 'a, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+}
+
+impl<'s, 'a, T: Target> ser::Serializer for PairSerializer<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Pair<'s, 'a, T>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    reject_scalars!(Error::Unsupported(NOT_A_PAIR));
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, _value: &V) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &V,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        if len != 2 {
+            return Err(Error::Unsupported(NOT_A_PAIR));
+        }
+        Ok(Pair {
+            form: self.form,
+            name: None,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unsupported(NOT_A_PAIR))
+    }
+}
+
+/// A (name, value) pair, whose name is serialized first.
+struct Pair<'s, 'a, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+    name: Option<String>,
+}
+
+impl<'s, 'a, T: Target> ser::SerializeTuple for Pair<'s, 'a, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        match self.name {
+            None => {
+                self.name = Some(value.serialize(NameSerializer)?);
+                Ok(())
+            }
+            Some(ref name) => value.serialize(ValueSerializer {
+                form: &mut *self.form,
+                name,
+                in_sequence: false,
+            }),
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes a name to a string.
+struct NameSerializer;
+
+impl ser::Serializer for NameSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    serialize_scalars_to_string!();
+
+    fn serialize_str(self, value: &str) -> Result<String, Error> {
+        Ok(value.into())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, _value: &V) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &V,
+    ) -> Result<String, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unsupported(INVALID_NAME))
+    }
+}
+
+/// Serializes the value for `name`, appending zero or more pairs.
+struct ValueSerializer<'s, 'a, 'n, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+    name: &'n str,
+    /// Whether this is an element of a sequence, which cannot contain sequences.
+    in_sequence: bool,
+}
+
+impl<'s, 'a, 'n, T: Target> ValueSerializer<'s, 'a, 'n, T> {
+    fn sequence(self) -> Result<SequenceValue<'s, 'a, 'n, T>, Error> {
+        if self.in_sequence {
+            return Err(Error::NestedSequence(self.name.into()));
+        }
+        Ok(SequenceValue {
+            form: self.form,
+            name: self.name,
+        })
+    }
+}
+
+impl<'s, 'a, 'n, T: Target> ser::Serializer for ValueSerializer<'s, 'a, 'n, T> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = SequenceValue<'s, 'a, 'n, T>;
+    type SerializeTuple = SequenceValue<'s, 'a, 'n, T>;
+    type SerializeTupleStruct = SequenceValue<'s, 'a, 'n, T>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    serialize_scalars_to_string!();
+
+    fn serialize_str(self, value: &str) -> Result<(), Error> {
+        self.form.append_pair(self.name, value);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<(), Error> {
+        Err(Error::Unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, value: &V) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.form.append_key_only(self.name);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &V,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &V,
+    ) -> Result<(), Error> {
+        Err(Error::Unsupported(VARIANT_WITH_DATA))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Unsupported(VARIANT_WITH_DATA))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::NestedMap(self.name.into()))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::NestedMap(self.name.into()))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unsupported(VARIANT_WITH_DATA))
+    }
+}
+
+/// A sequence value, serialized as one pair per element.
+struct SequenceValue<'s, 'a, 'n, T: Target> {
+    form: &'s mut Serializer<'a, T>,
+    name: &'n str,
+}
+
+impl<'s, 'a, 'n, T: Target> SequenceValue<'s, 'a, 'n, T> {
+    fn serialize_item<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        value.serialize(ValueSerializer {
+            form: &mut *self.form,
+            name: self.name,
+            in_sequence: true,
+        })
+    }
+}
+
+impl<'s, 'a, 'n, T: Target> ser::SerializeSeq for SequenceValue<'s, 'a, 'n, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, 'n, T: Target> ser::SerializeTuple for SequenceValue<'s, 'a, 'n, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'s, 'a, 'n, T: Target> ser::SerializeTupleStruct for SequenceValue<'s, 'a, 'n, T> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
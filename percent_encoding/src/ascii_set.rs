@@ -24,9 +24,9 @@ use core::{mem, ops};
 /// /// https://url.spec.whatwg.org/#fragment-percent-encode-set
 /// const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
 /// ```
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct AsciiSet {
-    mask: [Chunk; ASCII_RANGE_LEN / BITS_PER_CHUNK],
+    pub(crate) mask: [Chunk; ASCII_RANGE_LEN / BITS_PER_CHUNK],
 }
 
 type Chunk = u32;
@@ -41,18 +41,19 @@ impl AsciiSet {
         mask: [0; ASCII_RANGE_LEN / BITS_PER_CHUNK],
     };
 
-    /// Called with UTF-8 bytes rather than code points.
-    /// Not used for non-ASCII bytes.
-    pub(crate) const fn contains(&self, byte: u8) -> bool {
+    /// Return whether `byte` is in the set.
+    ///
+    /// Non-ASCII bytes are never in an `AsciiSet`,
+    /// even though percent-encoding with it encodes them.
+    pub const fn contains(&self, byte: u8) -> bool {
+        if !byte.is_ascii() {
+            return false;
+        }
         let chunk = self.mask[byte as usize / BITS_PER_CHUNK];
         let mask = 1 << (byte as usize % BITS_PER_CHUNK);
         (chunk & mask) != 0
     }
 
-    pub(crate) fn should_percent_encode(&self, byte: u8) -> bool {
-        !byte.is_ascii() || self.contains(byte)
-    }
-
     pub const fn add(&self, byte: u8) -> Self {
         let mut mask = self.mask;
         mask[byte as usize / BITS_PER_CHUNK] |= 1 << (byte as usize % BITS_PER_CHUNK);
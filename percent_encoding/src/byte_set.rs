@@ -0,0 +1,313 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::AsciiSet;
+use core::iter::FromIterator;
+use core::ops::{self, RangeInclusive};
+
+/// Represents a set of bytes, including non-ASCII bytes.
+///
+/// Unlike an [`AsciiSet`], which always encodes non-ASCII bytes,
+/// a `ByteSet` can leave some or all of them unencoded,
+/// for example to keep the UTF-8 of a string intact:
+///
+/// ```
+/// use percent_encoding::{utf8_percent_encode, ByteSet};
+/// use percent_encoding::sets::FRAGMENT;
+///
+/// const IRI_FRAGMENT: &ByteSet = &ByteSet::from_ascii_set(FRAGMENT).difference(ByteSet::NON_ASCII);
+///
+/// assert_eq!(utf8_percent_encode("für <Elise>", IRI_FRAGMENT).to_string(), "für%20%3CElise%3E");
+/// assert_eq!(utf8_percent_encode("für <Elise>", FRAGMENT).to_string(), "f%C3%BCr%20%3CElise%3E");
+/// ```
+///
+/// Non-ASCII bytes that are not in the set are only left unencoded
+/// in valid UTF-8 sequences without any byte in the set,
+/// so that the output is always a valid string.
+///
+/// Sets can also be built at runtime, from ranges, strings or iterators of bytes:
+///
+/// ```
+/// use percent_encoding::{utf8_percent_encode, ByteSet};
+///
+/// let reserved = String::from("$&+,/:;=?@");
+/// let set = ByteSet::NON_ASCII
+///     .add_range(0x00..=0x20)
+///     .add_all(reserved.as_bytes());
+/// assert_eq!(utf8_percent_encode("a b&c", &set).to_string(), "a%20b%26c");
+///
+/// let set: ByteSet = reserved.bytes().collect();
+/// assert_eq!(utf8_percent_encode("a b&c", &set).to_string(), "a b%26c");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+    mask: [Chunk; 256 / BITS_PER_CHUNK],
+}
+
+type Chunk = u32;
+
+const BITS_PER_CHUNK: usize = 32;
+
+impl ByteSet {
+    /// An empty set.
+    pub const EMPTY: Self = Self { mask: [0; 8] };
+
+    /// The set of non-ASCII bytes, 0x80 to 0xFF.
+    pub const NON_ASCII: Self = Self {
+        mask: [0, 0, 0, 0, !0, !0, !0, !0],
+    };
+
+    /// Return the set of the bytes in `set` and of all non-ASCII bytes,
+    /// which percent-encodes like `set`.
+    pub const fn from_ascii_set(set: &AsciiSet) -> Self {
+        let ascii = set.mask;
+        Self {
+            mask: [ascii[0], ascii[1], ascii[2], ascii[3], !0, !0, !0, !0],
+        }
+    }
+
+    /// Return whether `byte` is in the set.
+    pub const fn contains(&self, byte: u8) -> bool {
+        let chunk = self.mask[byte as usize / BITS_PER_CHUNK];
+        let mask = 1 << (byte as usize % BITS_PER_CHUNK);
+        (chunk & mask) != 0
+    }
+
+    /// Return the ASCII bytes of the set as the low 128 bits of a `u128`.
+    pub(crate) fn ascii_mask(&self) -> u128 {
+        let mask = &self.mask;
+        u128::from(mask[0])
+            | u128::from(mask[1]) << 32
+            | u128::from(mask[2]) << 64
+            | u128::from(mask[3]) << 96
+    }
+
+    /// Return the set with `byte` added.
+    pub const fn add(&self, byte: u8) -> Self {
+        let mut mask = self.mask;
+        mask[byte as usize / BITS_PER_CHUNK] |= 1 << (byte as usize % BITS_PER_CHUNK);
+        Self { mask }
+    }
+
+    /// Return the set with `byte` removed.
+    pub const fn remove(&self, byte: u8) -> Self {
+        let mut mask = self.mask;
+        mask[byte as usize / BITS_PER_CHUNK] &= !(1 << (byte as usize % BITS_PER_CHUNK));
+        Self { mask }
+    }
+
+    /// Return the set with all bytes of `range` added.
+    pub const fn add_range(&self, range: RangeInclusive<u8>) -> Self {
+        self.union(Self::range(*range.start(), *range.end()))
+    }
+
+    /// Return the set with all bytes of `range` removed.
+    pub const fn remove_range(&self, range: RangeInclusive<u8>) -> Self {
+        self.difference(Self::range(*range.start(), *range.end()))
+    }
+
+    /// Return the set with all of `bytes` added.
+    pub const fn add_all(&self, bytes: &[u8]) -> Self {
+        let mut set = *self;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.add(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Return the set with all of `bytes` removed.
+    pub const fn remove_all(&self, bytes: &[u8]) -> Self {
+        let mut set = *self;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.remove(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    const fn range(start: u8, end: u8) -> Self {
+        let mut mask = [0; 256 / BITS_PER_CHUNK];
+        let mut byte = start as usize;
+        while byte <= end as usize {
+            mask[byte / BITS_PER_CHUNK] |= 1 << (byte % BITS_PER_CHUNK);
+            byte += 1;
+        }
+        Self { mask }
+    }
+
+    /// Return the union of two sets.
+    pub const fn union(&self, other: Self) -> Self {
+        let (a, b) = (&self.mask, &other.mask);
+        let mask = [
+            a[0] | b[0],
+            a[1] | b[1],
+            a[2] | b[2],
+            a[3] | b[3],
+            a[4] | b[4],
+            a[5] | b[5],
+            a[6] | b[6],
+            a[7] | b[7],
+        ];
+        Self { mask }
+    }
+
+    /// Return the bytes of this set that are not in `other`.
+    pub const fn difference(&self, other: Self) -> Self {
+        let (a, b) = (&self.mask, &other.mask);
+        let mask = [
+            a[0] & !b[0],
+            a[1] & !b[1],
+            a[2] & !b[2],
+            a[3] & !b[3],
+            a[4] & !b[4],
+            a[5] & !b[5],
+            a[6] & !b[6],
+            a[7] & !b[7],
+        ];
+        Self { mask }
+    }
+
+    /// Return the negation of the set.
+    pub const fn complement(&self) -> Self {
+        let a = &self.mask;
+        let mask = [!a[0], !a[1], !a[2], !a[3], !a[4], !a[5], !a[6], !a[7]];
+        Self { mask }
+    }
+}
+
+impl From<&AsciiSet> for ByteSet {
+    fn from(set: &AsciiSet) -> Self {
+        Self::from_ascii_set(set)
+    }
+}
+
+impl FromIterator<u8> for ByteSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut set = Self::EMPTY;
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u8> for ByteSet {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for byte in iter {
+            *self = self.add(byte);
+        }
+    }
+}
+
+impl ops::Add for ByteSet {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl ops::Not for ByteSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+/// A set of bytes to percent-encode,
+/// as accepted by [`percent_encode`](crate::percent_encode)
+/// and [`utf8_percent_encode`](crate::utf8_percent_encode).
+pub trait EncodeSet {
+    /// Return the bytes to percent-encode.
+    fn byte_set(&self) -> ByteSet;
+}
+
+impl EncodeSet for AsciiSet {
+    #[inline]
+    fn byte_set(&self) -> ByteSet {
+        ByteSet::from_ascii_set(self)
+    }
+}
+
+impl EncodeSet for ByteSet {
+    #[inline]
+    fn byte_set(&self) -> ByteSet {
+        *self
+    }
+}
+
+impl<S: EncodeSet + ?Sized> EncodeSet for &S {
+    #[inline]
+    fn byte_set(&self) -> ByteSet {
+        (**self).byte_set()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CONTROLS, NON_ALPHANUMERIC};
+
+    #[test]
+    fn from_ascii_set() {
+        let set = ByteSet::from_ascii_set(NON_ALPHANUMERIC);
+        for byte in 0..=0xFF {
+            assert_eq!(set.contains(byte), !byte.is_ascii_alphanumeric());
+        }
+        let ascii = set.difference(ByteSet::NON_ASCII);
+        assert!((0x80..=0xFF).all(|byte| set.contains(byte) && !ascii.contains(byte)));
+    }
+
+    #[test]
+    fn const_operations() {
+        const SET: ByteSet = ByteSet::EMPTY.add(b'a').add(0xFF).remove(b'a');
+        const UNION: ByteSet = SET.union(ByteSet::EMPTY.add(0x80));
+        const COMPLEMENT: ByteSet = UNION.complement();
+        assert!(!SET.contains(b'a'));
+        assert!(SET.contains(0xFF));
+        assert!(UNION.contains(0x80) && UNION.contains(0xFF));
+        assert!(!COMPLEMENT.contains(0x80) && COMPLEMENT.contains(0x81));
+        assert_eq!(!COMPLEMENT, UNION);
+        assert_eq!(SET + ByteSet::EMPTY.add(0x80), UNION);
+        assert_eq!(ByteSet::from(CONTROLS), CONTROLS.byte_set());
+    }
+
+    #[test]
+    fn runtime_sets() {
+        let set = ByteSet::EMPTY
+            .add_range(b'a'..=b'z')
+            .remove_range(b'b'..=b'y');
+        assert!((0..=0xFF).all(|byte| set.contains(byte) == (byte == b'a' || byte == b'z')));
+        assert_eq!(
+            ByteSet::EMPTY.add_range(0xF0..=0xFF),
+            ByteSet::EMPTY.add_all(&[
+                0xF0, 0xF1, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA, 0xFB, 0xFC, 0xFD,
+                0xFE, 0xFF
+            ])
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = ByteSet::EMPTY.add_range(2..=1);
+        assert_eq!(empty, ByteSet::EMPTY);
+        assert_eq!(
+            ByteSet::NON_ASCII.remove_all(&[0x80, 0xFF]),
+            ByteSet::EMPTY.add_range(0x81..=0xFE)
+        );
+        let collected: ByteSet = (0x80..=0xFF).collect();
+        assert_eq!(collected, ByteSet::NON_ASCII);
+        assert_eq!(
+            ByteSet::from_ascii_set(NON_ALPHANUMERIC),
+            ByteSet::NON_ASCII
+                .add_range(0..=0x7F)
+                .remove_range(b'0'..=b'9')
+                .remove_range(b'A'..=b'Z')
+                .remove_range(b'a'..=b'z')
+        );
+    }
+}
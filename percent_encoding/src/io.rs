@@ -0,0 +1,378 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{decode_hex_pair, percent_encode_to, scan, utf8_char_len, ByteSet, EncodeSet};
+use alloc::{boxed::Box, string::String, vec};
+use std::io;
+
+/// An `io::Write` adapter that percent-encodes everything written through it.
+///
+/// Bytes are encoded like [`percent_encode`](crate::percent_encode) would encode
+/// all of them at once, even if a UTF-8 character is split across several writes:
+/// the start of an incomplete character is kept until the rest of it is written,
+/// or until [`into_inner`](Self::into_inner) is called.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::{PercentEncodingWriter, NON_ALPHANUMERIC};
+/// use std::io::Write;
+///
+/// let mut writer = PercentEncodingWriter::new(Vec::new(), NON_ALPHANUMERIC);
+/// writer.write_all(b"foo bar?")?;
+/// assert_eq!(writer.into_inner()?, b"foo%20bar%3F");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PercentEncodingWriter<W: io::Write> {
+    inner: W,
+    set: ByteSet,
+    /// The start of an incomplete non-ASCII character.
+    pending: [u8; 4],
+    pending_len: usize,
+    /// The encoding of the bytes of the current write, reused across writes.
+    buffer: String,
+}
+
+impl<W: io::Write> PercentEncodingWriter<W> {
+    /// Create a writer that percent-encodes the bytes in `set` before writing them to `inner`.
+    pub fn new<S: EncodeSet + ?Sized>(inner: W, set: &S) -> Self {
+        Self {
+            inner,
+            set: set.byte_set(),
+            pending: [0; 4],
+            pending_len: 0,
+            buffer: String::new(),
+        }
+    }
+
+    /// Return a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying writer.
+    ///
+    /// Writing directly to it bypasses the encoding.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Write the bytes of an incomplete character, if any, and return the underlying writer.
+    ///
+    /// Since they are not valid UTF-8, these bytes are percent-encoded.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        if self.pending_len > 0 {
+            self.buffer.clear();
+            percent_encode_to(
+                &self.pending[..self.pending_len],
+                &self.set,
+                &mut self.buffer,
+            );
+            self.inner.write_all(self.buffer.as_bytes())?;
+        }
+        Ok(self.inner)
+    }
+}
+
+impl<W: io::Write> io::Write for PercentEncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The pending bytes are only updated once the write succeeded,
+        // so that a failed write can be retried with the same `buf`.
+        let mut pending = self.pending;
+        let mut pending_len = self.pending_len;
+        let mut input = buf;
+        self.buffer.clear();
+        if pending_len > 0 {
+            let char_len = utf8_char_len(pending[0]).unwrap();
+            while pending_len < char_len {
+                match input.split_first() {
+                    Some((&byte, rest)) if is_continuation_byte(byte) => {
+                        pending[pending_len] = byte;
+                        pending_len += 1;
+                        input = rest;
+                    }
+                    // The pending bytes are not a valid character and are encoded as such.
+                    Some(_) => break,
+                    None => {
+                        self.pending = pending;
+                        self.pending_len = pending_len;
+                        return Ok(buf.len());
+                    }
+                }
+            }
+            percent_encode_to(&pending[..pending_len], &self.set, &mut self.buffer);
+        }
+        let complete_len = complete_prefix_len(input);
+        percent_encode_to(&input[..complete_len], &self.set, &mut self.buffer);
+        let incomplete = &input[complete_len..];
+        pending[..incomplete.len()].copy_from_slice(incomplete);
+        pending_len = incomplete.len();
+        self.inner.write_all(self.buffer.as_bytes())?;
+        self.pending = pending;
+        self.pending_len = pending_len;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Return the length of `bytes` without an incomplete non-ASCII character at the end.
+fn complete_prefix_len(bytes: &[u8]) -> usize {
+    let last_start = bytes
+        .iter()
+        .rev()
+        .take(3)
+        .position(|&byte| !is_continuation_byte(byte))
+        .map(|index| bytes.len() - 1 - index);
+    match last_start {
+        Some(start) => match utf8_char_len(bytes[start]) {
+            Some(char_len) if bytes.len() - start < char_len => start,
+            _ => bytes.len(),
+        },
+        None => bytes.len(),
+    }
+}
+
+/// An `io::Read` adapter that percent-decodes everything read through it.
+///
+/// Bytes are decoded like [`percent_decode`](crate::percent_decode) would decode
+/// all of them at once: a `%` that is not followed by two hexadecimal digits is left as-is.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::PercentDecodingReader;
+/// use std::io::Read;
+///
+/// let mut decoded = String::new();
+/// PercentDecodingReader::new(&b"foo%20bar%3f"[..]).read_to_string(&mut decoded)?;
+/// assert_eq!(decoded, "foo bar?");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PercentDecodingReader<R: io::Read> {
+    inner: R,
+    buffer: Box<[u8]>,
+    /// The range of `buffer` read from `inner` but not decoded yet.
+    start: usize,
+    end: usize,
+    eof: bool,
+}
+
+const READER_BUFFER_LEN: usize = 8 * 1024;
+
+impl<R: io::Read> PercentDecodingReader<R> {
+    /// Create a reader that percent-decodes the bytes read from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: vec![0; READER_BUFFER_LEN].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            eof: false,
+        }
+    }
+
+    /// Return a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from it bypasses the decoding, and the bytes buffered by this reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Return the underlying reader.
+    ///
+    /// Bytes read from it but not decoded yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        let read = self.inner.read(&mut self.buffer[self.end..])?;
+        self.end += read;
+        self.eof = read == 0;
+        Ok(())
+    }
+}
+
+impl<R: io::Read> io::Read for PercentDecodingReader<R> {
+    fn read(&mut self, output: &mut [u8]) -> io::Result<usize> {
+        if output.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            // Make sure that a `%` at the start is followed by what it would decode with.
+            while !self.eof && self.end - self.start < 3 {
+                self.fill_buffer()?;
+            }
+            let available = &self.buffer[self.start..self.end];
+            let mut read = 0;
+            let mut decoded = 0;
+            while read < output.len() && decoded < available.len() {
+                let run_len = scan::find_byte(b'%', &available[decoded..])
+                    .unwrap_or(available.len() - decoded)
+                    .min(output.len() - read);
+                output[read..read + run_len]
+                    .copy_from_slice(&available[decoded..decoded + run_len]);
+                read += run_len;
+                decoded += run_len;
+                if read == output.len() || decoded == available.len() {
+                    break;
+                }
+                // `available[decoded]` is a `%`.
+                if available.len() - decoded < 3 && !self.eof {
+                    break;
+                }
+                match decode_hex_pair(&available[decoded + 1..]) {
+                    Some(byte) => {
+                        output[read] = byte;
+                        decoded += 3;
+                    }
+                    None => {
+                        output[read] = b'%';
+                        decoded += 1;
+                    }
+                }
+                read += 1;
+            }
+            self.start += decoded;
+            if read > 0 || available.is_empty() {
+                return Ok(read);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{percent_decode, percent_encode, NON_ALPHANUMERIC};
+    use alloc::{string::ToString, vec::Vec};
+    use std::io::{Read, Write};
+
+    /// A reader that returns one byte at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, output: &mut [u8]) -> io::Result<usize> {
+            (&mut self.0).take(1).read(output)
+        }
+    }
+
+    #[test]
+    fn percent_encoding_writer() {
+        let set = ByteSet::from_ascii_set(NON_ALPHANUMERIC).difference(ByteSet::NON_ASCII);
+        let inputs: &[&[u8]] = &[
+            "é 💖?".as_bytes(),
+            b"\xC3",
+            b"\xC3A\xF0\x9F\x92",
+            b"\xF0\x9F\x92\xF0\x9F\x92\x96",
+            b"\x80\xBF\xFF",
+        ];
+        for &input in inputs {
+            for &chunk_len in &[1, 2, 3, 100] {
+                let mut writer = PercentEncodingWriter::new(Vec::new(), &set);
+                for chunk in input.chunks(chunk_len) {
+                    writer.write_all(chunk).unwrap();
+                }
+                let output = writer.into_inner().unwrap();
+                let expected = percent_encode(input, &set).to_string();
+                assert_eq!(
+                    String::from_utf8(output).unwrap(),
+                    expected,
+                    "{:?} in chunks of {}",
+                    input,
+                    chunk_len
+                );
+            }
+        }
+    }
+
+    /// A writer whose first write fails.
+    struct FailOnce(bool, Vec<u8>);
+
+    impl Write for FailOnce {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if core::mem::replace(&mut self.0, false) {
+                return Err(io::ErrorKind::Other.into());
+            }
+            self.1.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn percent_encoding_writer_retry() {
+        let mut writer = PercentEncodingWriter::new(FailOnce(false, Vec::new()), NON_ALPHANUMERIC);
+        writer.write_all(b"a\xC3").unwrap();
+        writer.get_mut().0 = true;
+        assert!(writer.write(b"\xA9 \xE2").is_err());
+        writer.write_all(b"\xA9 \xE2").unwrap();
+        writer.write_all(b"\x82\xAC").unwrap();
+        let output = writer.into_inner().unwrap().1;
+        assert_eq!(output, b"a%C3%A9%20%E2%82%AC");
+    }
+
+    #[test]
+    fn percent_decoding_reader() {
+        let inputs: &[&[u8]] = &[
+            b"",
+            b"%",
+            b"%4",
+            b"%41",
+            b"%%41%",
+            b"a%zz%2f%2F%F0%9F%92%96%",
+        ];
+        for &input in inputs {
+            let expected: Vec<u8> = percent_decode(input).collect();
+            let mut output = Vec::new();
+            PercentDecodingReader::new(Trickle(input))
+                .read_to_end(&mut output)
+                .unwrap();
+            assert_eq!(output, expected, "{:?}", input);
+            let mut output = Vec::new();
+            PercentDecodingReader::new(input)
+                .read_to_end(&mut output)
+                .unwrap();
+            assert_eq!(output, expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn percent_decoding_reader_small_reads() {
+        let input = b"%41%42 %43".repeat(1000);
+        let mut reader = PercentDecodingReader::new(&input[..]);
+        let mut output = Vec::new();
+        let mut chunk = [0; 2];
+        loop {
+            match reader.read(&mut chunk).unwrap() {
+                0 => break,
+                read => output.extend_from_slice(&chunk[..read]),
+            }
+        }
+        assert_eq!(output, b"AB C".repeat(1000));
+    }
+}
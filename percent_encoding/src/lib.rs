@@ -18,11 +18,11 @@
 //! depends on the context.
 //! The `?` question mark mentioned above is not a separator when used literally
 //! inside of a query string, and therefore does not need to be encoded.
-//! The [`AsciiSet`] parameter of [`percent_encode`] and [`utf8_percent_encode`]
+//! The [`AsciiSet`] or [`ByteSet`] parameter of [`percent_encode`] and [`utf8_percent_encode`]
 //! lets callers configure this.
 //!
-//! This crate deliberately does not provide many different sets.
-//! Users should consider in what context the encoded string will be used,
+//! The [`sets`] module provides the sets defined by the URL Standard and by RFC 3986.
+//! For other contexts, users should consider how the encoded string will be used,
 //! read relevant specifications, and define their own set.
 //! This is done by using the `add` method of an existing set.
 //!
@@ -35,12 +35,19 @@
 //! const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
 //!
 //! assert_eq!(utf8_percent_encode("foo <bar>", FRAGMENT).to_string(), "foo%20%3Cbar%3E");
+//! assert_eq!(*FRAGMENT, *percent_encoding::sets::FRAGMENT);
 //! ```
+//!
+//! # Performance
+//!
+//! Encoding and decoding skip runs of bytes that stay unchanged several bytes at a time.
+//! This uses SSE2 on x86 and x86_64 when it is enabled at compile time.
+//! Other targets, including aarch64, use portable word-at-a-time code instead:
+//! NEON intrinsics need Rust 1.59, newer than the 1.51 this crate supports.
 #![no_std]
 
-// For forwards compatibility
 #[cfg(feature = "std")]
-extern crate std as _;
+extern crate std;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
@@ -54,12 +61,30 @@ use alloc::{
 use core::{fmt, slice, str};
 
 pub use self::ascii_set::{AsciiSet, CONTROLS, NON_ALPHANUMERIC};
+pub use self::byte_set::{ByteSet, EncodeSet};
+#[cfg(feature = "std")]
+pub use self::io::{PercentDecodingReader, PercentEncodingWriter};
+#[cfg(feature = "alloc")]
+pub use self::reencode::reencode;
+#[cfg(feature = "alloc")]
+pub use self::strict::{
+    percent_decode_strict, percent_decode_strict_except, DecodeError, DecodeErrorKind,
+};
 
 mod ascii_set;
+mod byte_set;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "alloc")]
+mod reencode;
+mod scan;
+pub mod sets;
+#[cfg(feature = "alloc")]
+mod strict;
 
 /// Return the percent-encoding of the given byte.
 ///
-/// This is unconditional, unlike `percent_encode()` which has an `AsciiSet` parameter.
+/// This is unconditional, unlike `percent_encode()` which has a set parameter.
 ///
 /// # Examples
 ///
@@ -98,7 +123,12 @@ pub fn percent_encode_byte(byte: u8) -> &'static str {
 
 /// Percent-encode the given bytes with the given set.
 ///
-/// Non-ASCII bytes and bytes in `ascii_set` are encoded.
+/// Bytes in `set` are encoded. With an [`AsciiSet`], this includes all non-ASCII bytes.
+/// Non-ASCII bytes that are not in a [`ByteSet`] are left as-is only in valid UTF-8
+/// sequences without any byte in the set, other bytes are encoded.
+///
+/// The set is copied into the return value, so it does not need to outlive it:
+/// sets built at runtime, for example with [`ByteSet::add_all`], work as well as constants.
 ///
 /// The return type:
 ///
@@ -114,10 +144,14 @@ pub fn percent_encode_byte(byte: u8) -> &'static str {
 /// assert_eq!(percent_encode(b"foo bar?", NON_ALPHANUMERIC).to_string(), "foo%20bar%3F");
 /// ```
 #[inline]
-pub fn percent_encode<'a>(input: &'a [u8], ascii_set: &'static AsciiSet) -> PercentEncode<'a> {
+pub fn percent_encode<'a, S>(input: &'a [u8], set: &S) -> PercentEncode<'a>
+where
+    S: EncodeSet + ?Sized,
+{
     PercentEncode {
         bytes: input,
-        ascii_set,
+        set: set.byte_set(),
+        iri: false,
     }
 }
 
@@ -133,43 +167,165 @@ pub fn percent_encode<'a>(input: &'a [u8], ascii_set: &'static AsciiSet) -> Perc
 /// assert_eq!(utf8_percent_encode("foo bar?", NON_ALPHANUMERIC).to_string(), "foo%20bar%3F");
 /// ```
 #[inline]
-pub fn utf8_percent_encode<'a>(input: &'a str, ascii_set: &'static AsciiSet) -> PercentEncode<'a> {
-    percent_encode(input.as_bytes(), ascii_set)
+pub fn utf8_percent_encode<'a, S>(input: &'a str, set: &S) -> PercentEncode<'a>
+where
+    S: EncodeSet + ?Sized,
+{
+    percent_encode(input.as_bytes(), set)
+}
+
+/// Percent-encode a string for an IRI, keeping the non-ASCII characters that
+/// [RFC 3987](https://www.rfc-editor.org/rfc/rfc3987#section-2.2) allows as `ucschar`.
+///
+/// ASCII bytes are encoded if they are in `set`.
+/// Non-ASCII characters are kept if they are `ucschar`, regardless of `set`,
+/// and the bytes of their UTF-8 encoding are encoded otherwise:
+/// this includes C1 controls, the private use areas, tags and noncharacters.
+///
+/// See [`percent_encode`] regarding the return type.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::utf8_percent_encode_iri;
+/// use percent_encoding::sets::PATH_SEGMENT;
+///
+/// assert_eq!(
+///     utf8_percent_encode_iri("résumé 2/3\u{85}", PATH_SEGMENT).to_string(),
+///     "résumé%202%2F3%C2%85"
+/// );
+/// ```
+#[inline]
+pub fn utf8_percent_encode_iri<'a, S>(input: &'a str, set: &S) -> PercentEncode<'a>
+where
+    S: EncodeSet + ?Sized,
+{
+    PercentEncode {
+        bytes: input.as_bytes(),
+        set: set.byte_set(),
+        iri: true,
+    }
 }
 
-/// The return type of [`percent_encode`] and [`utf8_percent_encode`].
+/// Return the length of a UTF-8 character starting with `first_byte`,
+/// or `None` if it is not the first byte of a non-ASCII character.
+fn utf8_char_len(first_byte: u8) -> Option<usize> {
+    match first_byte {
+        0xC2..=0xDF => Some(2),
+        0xE0..=0xEF => Some(3),
+        0xF0..=0xF4 => Some(4),
+        _ => None,
+    }
+}
+
+/// Return whether `c` is a non-ASCII character allowed in IRIs.
+///
+/// <https://www.rfc-editor.org/rfc/rfc3987#section-2.2>
+fn is_ucschar(c: char) -> bool {
+    let c = u32::from(c);
+    match c {
+        0xA0..=0xD7FF | 0xF900..=0xFDCF | 0xFDF0..=0xFFEF => true,
+        // Planes 1 to 14, without the last two code points of each plane
+        // and without the first 0x1000 code points of plane 14.
+        0x10000..=0xEFFFD => c & 0xFFFE != 0xFFFE && !(0xE0000..0xE1000).contains(&c),
+        _ => false,
+    }
+}
+
+/// The return type of [`percent_encode`], [`utf8_percent_encode`]
+/// and [`utf8_percent_encode_iri`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PercentEncode<'a> {
     bytes: &'a [u8],
-    ascii_set: &'static AsciiSet,
+    set: ByteSet,
+    iri: bool,
+}
+
+impl PercentEncode<'_> {
+    /// Write the percent-encoded string to `output`, such as a `String` or a `fmt::Formatter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+    /// use std::fmt::Write;
+    ///
+    /// let mut request_line = String::from("GET /search?q=");
+    /// utf8_percent_encode("café & co", NON_ALPHANUMERIC).write_to(&mut request_line)?;
+    /// write!(request_line, " HTTP/1.1")?;
+    /// assert_eq!(request_line, "GET /search?q=caf%C3%A9%20%26%20co HTTP/1.1");
+    /// # Ok::<(), std::fmt::Error>(())
+    /// ```
+    pub fn write_to<W: fmt::Write + ?Sized>(self, output: &mut W) -> fmt::Result {
+        for chunk in self {
+            output.write_str(chunk)?
+        }
+        Ok(())
+    }
+
+    /// Return the length of the character at the start of `bytes` if it is left as-is,
+    /// or 0 if its first byte is encoded.
+    ///
+    /// Not inlined, so that loops over ASCII bytes stay small.
+    #[inline(never)]
+    fn unchanged_len(&self, bytes: &[u8]) -> usize {
+        let first_byte = bytes[0];
+        if first_byte.is_ascii() {
+            return !self.set.contains(first_byte) as usize;
+        }
+        if !self.iri && self.set.contains(first_byte) {
+            return 0;
+        }
+        let len = match utf8_char_len(first_byte) {
+            Some(len) => len,
+            None => return 0,
+        };
+        let c = match bytes.get(..len).map(str::from_utf8) {
+            Some(Ok(s)) => s.chars().next().unwrap(),
+            _ => return 0,
+        };
+        let unchanged = if self.iri {
+            is_ucschar(c)
+        } else {
+            bytes[..len].iter().all(|&byte| !self.set.contains(byte))
+        };
+        if unchanged {
+            len
+        } else {
+            0
+        }
+    }
 }
 
 impl<'a> Iterator for PercentEncode<'a> {
     type Item = &'a str;
 
     fn next(&mut self) -> Option<&'a str> {
-        if let Some((&first_byte, remaining)) = self.bytes.split_first() {
-            if self.ascii_set.should_percent_encode(first_byte) {
-                self.bytes = remaining;
-                Some(percent_encode_byte(first_byte))
-            } else {
-                // The unsafe blocks here are appropriate because the bytes are
-                // confirmed as a subset of UTF-8 in should_percent_encode.
-                for (i, &byte) in remaining.iter().enumerate() {
-                    if self.ascii_set.should_percent_encode(byte) {
-                        // 1 for first_byte + i for previous iterations of this loop
-                        let (unchanged_slice, remaining) = self.bytes.split_at(1 + i);
-                        self.bytes = remaining;
-                        return Some(unsafe { str::from_utf8_unchecked(unchanged_slice) });
-                    }
-                }
-                let unchanged_slice = self.bytes;
-                self.bytes = &[][..];
-                Some(unsafe { str::from_utf8_unchecked(unchanged_slice) })
+        let (&first_byte, remaining) = self.bytes.split_first()?;
+        if first_byte.is_ascii() && self.set.contains(first_byte) {
+            self.bytes = remaining;
+            return Some(percent_encode_byte(first_byte));
+        }
+        let mut len = 0;
+        let mut ranges = None;
+        loop {
+            len += scan::ascii_unchanged_len(&self.bytes[len..], &self.set, &mut ranges);
+            if len == self.bytes.len() || self.bytes[len].is_ascii() {
+                break;
+            }
+            match self.unchanged_len(&self.bytes[len..]) {
+                0 => break,
+                char_len => len += char_len,
             }
-        } else {
-            None
         }
+        if len == 0 {
+            self.bytes = remaining;
+            return Some(percent_encode_byte(first_byte));
+        }
+        let (unchanged_slice, remaining) = self.bytes.split_at(len);
+        self.bytes = remaining;
+        // SAFETY: `unchanged_len` only accepts ASCII bytes and complete UTF-8 sequences.
+        Some(unsafe { str::from_utf8_unchecked(unchanged_slice) })
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -183,10 +339,7 @@ impl<'a> Iterator for PercentEncode<'a> {
 
 impl fmt::Display for PercentEncode<'_> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for c in (*self).clone() {
-            formatter.write_str(c)?
-        }
-        Ok(())
+        self.clone().write_to(formatter)
     }
 }
 
@@ -208,6 +361,53 @@ impl<'a> From<PercentEncode<'a>> for Cow<'a, str> {
     }
 }
 
+/// Percent-encode the given bytes with the given set, appending to `output`.
+///
+/// This is equivalent to `output.extend(percent_encode(input, set))`, but faster for long inputs.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::{percent_encode_to, NON_ALPHANUMERIC};
+///
+/// let mut output = String::from("q=");
+/// percent_encode_to(b"foo bar?", NON_ALPHANUMERIC, &mut output);
+/// assert_eq!(output, "q=foo%20bar%3F");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn percent_encode_to<S>(input: &[u8], set: &S, output: &mut String)
+where
+    S: EncodeSet + ?Sized,
+{
+    let encoder = percent_encode(input, set);
+    let set = &encoder.set;
+    let mut ranges = None;
+    output.reserve(input.len());
+    let mut unchanged_start = 0;
+    let mut index = 0;
+    loop {
+        index += scan::ascii_unchanged_len(&input[index..], set, &mut ranges);
+        let byte = match input.get(index) {
+            Some(&byte) => byte,
+            None => break,
+        };
+        if !byte.is_ascii() {
+            let unchanged_len = encoder.unchanged_len(&input[index..]);
+            if unchanged_len > 0 {
+                index += unchanged_len;
+                continue;
+            }
+        }
+        // SAFETY: `unchanged_len` only accepts ASCII bytes and complete UTF-8 sequences.
+        output.push_str(unsafe { str::from_utf8_unchecked(&input[unchanged_start..index]) });
+        output.push_str(percent_encode_byte(byte));
+        index += 1;
+        unchanged_start = index;
+    }
+    // SAFETY: as above.
+    output.push_str(unsafe { str::from_utf8_unchecked(&input[unchanged_start..]) });
+}
+
 /// Percent-decode the given string.
 ///
 /// <https://url.spec.whatwg.org/#string-percent-decode>
@@ -223,6 +423,7 @@ pub fn percent_decode_str(input: &str) -> PercentDecode<'_> {
 /// <https://url.spec.whatwg.org/#percent-decode>
 ///
 /// Any sequence of `%` followed by two hexadecimal digits is decoded.
+/// Other `%` bytes are left as-is: see [`percent_decode_strict`] to reject them instead.
 /// The return type:
 ///
 /// * Implements `Into<Cow<u8>>` borrowing `input` when it contains no percent-encoded sequence,
@@ -243,20 +444,59 @@ pub fn percent_decode(input: &[u8]) -> PercentDecode<'_> {
     }
 }
 
+/// Percent-decode the given bytes, appending to `output`.
+///
+/// This is equivalent to `output.extend(percent_decode(input))`, but faster for long inputs.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::percent_decode_into;
+///
+/// let mut output = b"q=".to_vec();
+/// percent_decode_into(b"foo%20bar%3f", &mut output);
+/// assert_eq!(output, b"q=foo bar?");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn percent_decode_into(input: &[u8], output: &mut Vec<u8>) {
+    output.reserve(input.len());
+    let mut remaining = input;
+    while let Some(index) = scan::find_byte(b'%', remaining) {
+        output.extend_from_slice(&remaining[..index]);
+        match decode_hex_pair(&remaining[index + 1..]) {
+            Some(byte) => {
+                output.push(byte);
+                remaining = &remaining[index + 3..];
+            }
+            None => {
+                output.push(b'%');
+                remaining = &remaining[index + 1..];
+            }
+        }
+    }
+    output.extend_from_slice(remaining);
+}
+
 /// The return type of [`percent_decode`].
 #[derive(Clone, Debug)]
 pub struct PercentDecode<'a> {
     bytes: slice::Iter<'a, u8>,
 }
 
-fn after_percent_sign(iter: &mut slice::Iter<'_, u8>) -> Option<u8> {
-    let mut cloned_iter = iter.clone();
-    let h = char::from(*cloned_iter.next()?).to_digit(16)?;
-    let l = char::from(*cloned_iter.next()?).to_digit(16)?;
-    *iter = cloned_iter;
+/// Decode the two hexadecimal digits at the start of `bytes`, if any.
+#[inline]
+fn decode_hex_pair(bytes: &[u8]) -> Option<u8> {
+    let h = char::from(*bytes.first()?).to_digit(16)?;
+    let l = char::from(*bytes.get(1)?).to_digit(16)?;
     Some(h as u8 * 0x10 + l as u8)
 }
 
+fn after_percent_sign(iter: &mut slice::Iter<'_, u8>) -> Option<u8> {
+    let byte = decode_hex_pair(iter.as_slice())?;
+    iter.nth(1);
+    Some(byte)
+}
+
 impl Iterator for PercentDecode<'_> {
     type Item = u8;
 
@@ -290,16 +530,16 @@ impl<'a> PercentDecode<'a> {
     /// If the percent-decoding is different from the input, return it as a new bytes vector.
     #[cfg(feature = "alloc")]
     fn if_any(&self) -> Option<Vec<u8>> {
-        let mut bytes_iter = self.bytes.clone();
-        while bytes_iter.any(|&b| b == b'%') {
-            if let Some(decoded_byte) = after_percent_sign(&mut bytes_iter) {
-                let initial_bytes = self.bytes.as_slice();
-                let unchanged_bytes_len = initial_bytes.len() - bytes_iter.len() - 3;
-                let mut decoded = initial_bytes[..unchanged_bytes_len].to_owned();
-                decoded.push(decoded_byte);
-                decoded.extend(PercentDecode { bytes: bytes_iter });
+        let bytes = self.bytes.as_slice();
+        let mut start = 0;
+        while let Some(index) = scan::find_byte(b'%', &bytes[start..]) {
+            let percent = start + index;
+            if decode_hex_pair(&bytes[percent + 1..]).is_some() {
+                let mut decoded = bytes[..percent].to_owned();
+                percent_decode_into(&bytes[percent..], &mut decoded);
                 return Some(decoded);
             }
+            start = percent + 1;
         }
         // Nothing to decode
         None
@@ -418,6 +658,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn percent_encode_byte_set() {
+        const SET: &ByteSet =
+            &ByteSet::from_ascii_set(NON_ALPHANUMERIC).difference(ByteSet::NON_ASCII);
+        assert_eq!(
+            super::utf8_percent_encode("é 💖?", SET).collect::<String>(),
+            "é%20💖%3F"
+        );
+        // Invalid or truncated UTF-8 is still encoded.
+        let encoded = percent_encode(b"\xC3\xA9\xC3\xFF\xF0\x9F\x92", SET);
+        assert_eq!(encoded.collect::<String>(), "é%C3%FF%F0%9F%92");
+        // So are characters with any byte in the set.
+        const NO_A9: &ByteSet = &SET.add(0xA9);
+        assert_eq!(
+            super::utf8_percent_encode("éè", NO_A9).collect::<String>(),
+            "%C3%A9è"
+        );
+    }
+
+    #[test]
+    fn percent_encode_with_runtime_set() {
+        let config = String::from("?#");
+        let ascii_set = config.bytes().fold(*CONTROLS, |set, byte| set.add(byte));
+        let byte_set = ByteSet::from_ascii_set(CONTROLS).add_all(config.as_bytes());
+        let encoded = percent_encode(b"a?b#\x00\xFF", &ascii_set);
+        assert_eq!(encoded.collect::<String>(), "a%3Fb%23%00%FF");
+        let encoded = percent_encode(b"a?b#\x00\xFF", &byte_set);
+        assert_eq!(encoded.collect::<String>(), "a%3Fb%23%00%FF");
+    }
+
+    #[test]
+    fn utf8_percent_encode_iri() {
+        let encode =
+            |input| super::utf8_percent_encode_iri(input, NON_ALPHANUMERIC).collect::<String>();
+        assert_eq!(encode("é 💖?"), "é%20💖%3F");
+        assert_eq!(encode("\u{85}\u{A0}"), "%C2%85\u{A0}");
+        assert_eq!(
+            encode("\u{E000}\u{FDD0}\u{FFFF}"),
+            "%EE%80%80%EF%B7%90%EF%BF%BF"
+        );
+        assert_eq!(encode("\u{1FFFD}\u{1FFFE}"), "\u{1FFFD}%F0%9F%BF%BE");
+        assert_eq!(encode("\u{E0001}\u{E1000}"), "%F3%A0%80%81\u{E1000}");
+    }
+
+    #[test]
+    fn percent_encode_to() {
+        let mut output = String::from("a=");
+        let input = "é 💖?\x00 long enough to be scanned a word at a time";
+        super::percent_encode_to(input.as_bytes(), NON_ALPHANUMERIC, &mut output);
+        let expected = "a=".to_owned()
+            + &super::utf8_percent_encode(input, NON_ALPHANUMERIC).collect::<String>();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn percent_decode_into() {
+        let cases: &[&[u8]] = &[
+            b"",
+            b"%",
+            b"%2",
+            b"%%2f",
+            b"%zz%20",
+            b"foo%20bar%3f and some text long enough for a vector %F0%9F%92%96%",
+        ];
+        for &input in cases {
+            let mut output = b"a=".to_vec();
+            super::percent_decode_into(input, &mut output);
+            let mut expected = b"a=".to_vec();
+            expected.extend(super::percent_decode(input));
+            assert_eq!(output, expected);
+            assert_eq!(Cow::from(super::percent_decode(input)), &expected[2..]);
+        }
+    }
+
     #[test]
     fn percent_decode() {
         assert_eq!(
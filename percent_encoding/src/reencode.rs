@@ -0,0 +1,243 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{decode_hex_pair, percent_encode, percent_encode_byte, EncodeSet, PercentEncode};
+use alloc::{borrow::Cow, string::String};
+use core::str;
+
+/// Normalize the percent-encoding of a string for the given set, in one pass.
+///
+/// * Bytes in `set` that are not percent-encoded are encoded,
+///   like [`utf8_percent_encode`](crate::utf8_percent_encode) does.
+/// * Percent-encoded bytes that are not in `set` are decoded,
+///   as long as they decode to ASCII or to complete UTF-8 characters.
+/// * Remaining percent-encoded sequences use uppercase hexadecimal digits.
+///
+/// A `%` that starts a percent-encoded sequence is never encoded again,
+/// and `%25` is never decoded, since that would create a new sequence.
+/// Other `%` bytes are encoded if `%` is in `set`.
+/// When they are left as-is, the hexadecimal digits percent-encoded right after them
+/// (as in `%%341`) are not decoded either, for the same reason.
+///
+/// The result borrows `input` when it is already normalized.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::reencode;
+/// use percent_encoding::sets::PATH_SEGMENT;
+///
+/// assert_eq!(reencode("a%2fb%7e%20c d", PATH_SEGMENT), "a%2Fb~%20c%20d");
+/// assert_eq!(reencode("caf%C3%A9 100%", PATH_SEGMENT), "caf%C3%A9%20100%25");
+/// ```
+pub fn reencode<'a, S>(input: &'a str, set: &S) -> Cow<'a, str>
+where
+    S: EncodeSet + ?Sized,
+{
+    let encoder = percent_encode(input.as_bytes(), set);
+    let set = set.byte_set();
+    let bytes = input.as_bytes();
+    let mut output = Output {
+        input,
+        string: None,
+        unchanged_start: 0,
+    };
+    // The index of the last `%` left as-is that does not start a percent-encoded sequence.
+    let mut last_stray_percent = None;
+    let mut index = 0;
+    while let Some(&byte) = bytes.get(index) {
+        if byte == b'%' {
+            if let Some(decoded) = decode_hex_pair(&bytes[index + 1..]) {
+                // Whether decoding to a hexadecimal digit would complete a sequence
+                // started by a stray `%`, either right before or before another digit.
+                let follows_stray_percent = || match last_stray_percent {
+                    Some(percent) if percent + 1 == index => true,
+                    Some(percent) if percent + 2 == index => {
+                        bytes[index - 1].is_ascii_hexdigit() && !set.contains(bytes[index - 1])
+                    }
+                    _ => false,
+                };
+                let decoded_len = if decoded == b'%'
+                    || set.contains(decoded)
+                    || (decoded.is_ascii_hexdigit() && follows_stray_percent())
+                {
+                    0
+                } else if decoded.is_ascii() {
+                    1
+                } else {
+                    decoded_char_len(&bytes[index..], &encoder)
+                };
+                if decoded_len == 0 {
+                    let encoded = percent_encode_byte(decoded);
+                    if bytes[index..index + 3] != *encoded.as_bytes() {
+                        output.replace(index, index + 3).push_str(encoded);
+                    }
+                    index += 3;
+                } else {
+                    let end = index + 3 * decoded_len;
+                    let mut utf8 = [0; 4];
+                    for (i, byte) in utf8[..decoded_len].iter_mut().enumerate() {
+                        *byte = decode_hex_pair(&bytes[index + 3 * i + 1..]).unwrap();
+                    }
+                    // `decoded_char_len` checked that this is valid UTF-8.
+                    let decoded = str::from_utf8(&utf8[..decoded_len]).unwrap();
+                    output.replace(index, end).push_str(decoded);
+                    index = end;
+                }
+                continue;
+            }
+        }
+        let unchanged_len = if byte.is_ascii() {
+            !set.contains(byte) as usize
+        } else {
+            encoder.unchanged_len(&bytes[index..])
+        };
+        if unchanged_len > 0 {
+            if byte == b'%' {
+                last_stray_percent = Some(index);
+            }
+            index += unchanged_len;
+            continue;
+        }
+        // Encode the whole character, as `percent_encode` does for valid UTF-8.
+        let end = index + input[index..].chars().next().unwrap().len_utf8();
+        let string = output.replace(index, end);
+        for &byte in &bytes[index..end] {
+            string.push_str(percent_encode_byte(byte));
+        }
+        index = end;
+    }
+    output.finish()
+}
+
+/// Return the length of the UTF-8 character percent-encoded at the start of `bytes`
+/// if it is complete and left as-is by `encoder` when decoded, or 0.
+fn decoded_char_len(bytes: &[u8], encoder: &PercentEncode<'_>) -> usize {
+    let mut utf8 = [0; 4];
+    let mut len = 0;
+    while len < utf8.len() {
+        match bytes
+            .get(3 * len..)
+            .filter(|rest| rest.first() == Some(&b'%'))
+            .and_then(|rest| decode_hex_pair(&rest[1..]))
+        {
+            Some(byte) if len == 0 || byte & 0xC0 == 0x80 => utf8[len] = byte,
+            _ => break,
+        }
+        len += 1;
+    }
+    if len == 0 {
+        return 0;
+    }
+    encoder.unchanged_len(&utf8[..len])
+}
+
+/// A copy-on-write output that only allocates once something changes.
+struct Output<'a> {
+    input: &'a str,
+    string: Option<String>,
+    /// The start of the part of `input` not yet copied into `string`.
+    unchanged_start: usize,
+}
+
+impl<'a> Output<'a> {
+    /// Return the output string, after `input` up to `start`,
+    /// to which the caller appends the replacement of `input[start..end]`.
+    fn replace(&mut self, start: usize, end: usize) -> &mut String {
+        let capacity = self.input.len();
+        let string = self
+            .string
+            .get_or_insert_with(|| String::with_capacity(capacity));
+        string.push_str(&self.input[self.unchanged_start..start]);
+        self.unchanged_start = end;
+        string
+    }
+
+    fn finish(self) -> Cow<'a, str> {
+        match self.string {
+            None => Cow::Borrowed(self.input),
+            Some(mut string) => {
+                string.push_str(&self.input[self.unchanged_start..]);
+                Cow::Owned(string)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sets::{PATH, QUERY_OR_FRAGMENT};
+    use crate::{ByteSet, CONTROLS, NON_ALPHANUMERIC};
+
+    #[test]
+    fn reencode() {
+        let cases = [
+            ("", ""),
+            ("abc", "abc"),
+            ("%41%2f%2F%7e%3c", "A//~%3C"),
+            ("a b", "a%20b"),
+            ("%25%2525", "%25%2525"),
+            ("100%", "100%25"),
+            ("%zz%4", "%25zz%254"),
+            ("%C3%A9", "%C3%A9"),
+            ("é", "%C3%A9"),
+            ("\u{0}", "%00"),
+        ];
+        for &(input, expected) in &cases {
+            assert_eq!(
+                super::reencode(input, QUERY_OR_FRAGMENT),
+                expected,
+                "{:?}",
+                input
+            );
+        }
+        assert!(matches!(
+            super::reencode("/%20%C3%A9~", QUERY_OR_FRAGMENT),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(super::reencode("%zz %", PATH), "%zz%20%");
+        // Decoding must not create a sequence with a `%` left as-is.
+        let cases = [
+            ("%%341", "%%341"),
+            ("a%%46F", "a%%46F"),
+            ("%4%31", "%4%31"),
+            ("%%4%61", "%%4%61"),
+            ("%%zz%41", "%%zzA"),
+            ("%%34%31", "%%341"),
+            ("%%3a", "%:"),
+        ];
+        for &(input, expected) in &cases {
+            let output = super::reencode(input, PATH);
+            assert_eq!(output, expected, "{:?}", input);
+            assert_eq!(super::reencode(&output, PATH), output, "{:?}", input);
+        }
+        assert_eq!(super::reencode("%2f%61", NON_ALPHANUMERIC), "%2Fa");
+    }
+
+    #[test]
+    fn reencode_non_ascii() {
+        let set = ByteSet::from_ascii_set(CONTROLS).difference(ByteSet::NON_ASCII);
+        let cases = [
+            ("%c3%a9", "é"),
+            ("%F0%9F%92%96!", "💖!"),
+            // Invalid or incomplete sequences stay encoded.
+            ("%c3", "%C3"),
+            ("%c3%41", "%C3A"),
+            ("%a9%c3", "%A9%C3"),
+            ("%F0%9F%92", "%F0%9F%92"),
+            ("%c3%a9%a9", "é%A9"),
+            ("%ff", "%FF"),
+        ];
+        for &(input, expected) in &cases {
+            assert_eq!(super::reencode(input, &set), expected, "{:?}", input);
+        }
+        // Characters with some byte in the set stay encoded.
+        assert_eq!(super::reencode("%c3%a9", &set.add(0xA9)), "%C3%A9");
+    }
+}
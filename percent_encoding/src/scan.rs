@@ -0,0 +1,403 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scanning for the next byte that needs work, several bytes at a time.
+//!
+//! `find_byte` and `ascii_unchanged_len` use SSE2 on x86 and x86_64
+//! when it is enabled at compile time (it always is on x86_64),
+//! and word-at-a-time code otherwise:
+//! for `ascii_unchanged_len`, only with sets of up to `MAX_WORD_RANGES` ranges.
+//! aarch64 also uses the word-at-a-time code, since NEON intrinsics were stabilized
+//! in Rust 1.59, after this crate’s minimum supported version.
+
+// Only the bulk decoding functions, which need `alloc`, use `find_byte`.
+#![cfg_attr(not(feature = "alloc"), allow(dead_code))]
+
+use crate::ByteSet;
+use core::convert::TryInto;
+use core::mem::size_of;
+
+const WORD_LEN: usize = size_of::<usize>();
+const LOW_BITS: usize = usize::from_ne_bytes([0x01; WORD_LEN]);
+const HIGH_BITS: usize = usize::from_ne_bytes([0x80; WORD_LEN]);
+
+/// Return the index of the first `needle` in `haystack`.
+#[inline]
+pub(crate) fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    {
+        sse2::find_byte(needle, haystack)
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    )))]
+    {
+        find_byte_swar(needle, haystack)
+    }
+}
+
+/// Return whether any byte of `word` is zero.
+#[inline]
+fn has_zero_byte(word: usize) -> bool {
+    word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS != 0
+}
+
+#[inline]
+fn find_byte_swar(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let repeated = LOW_BITS * usize::from(needle);
+    let mut chunks = haystack.chunks_exact(WORD_LEN);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap());
+        if has_zero_byte(word ^ repeated) {
+            break;
+        }
+        offset += WORD_LEN;
+    }
+    haystack[offset..]
+        .iter()
+        .position(|&byte| byte == needle)
+        .map(|index| offset + index)
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+mod sse2 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    const VECTOR_LEN: usize = 16;
+
+    #[inline]
+    pub(super) fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let mut offset = 0;
+        // SAFETY: SSE2 is enabled at compile time,
+        // and every load reads 16 bytes within `haystack`.
+        unsafe {
+            let repeated = _mm_set1_epi8(needle as i8);
+            while offset + VECTOR_LEN <= haystack.len() {
+                let chunk = _mm_loadu_si128(haystack.as_ptr().add(offset) as *const __m128i);
+                let matches = _mm_movemask_epi8(_mm_cmpeq_epi8(chunk, repeated));
+                if matches != 0 {
+                    return Some(offset + matches.trailing_zeros() as usize);
+                }
+                offset += VECTOR_LEN;
+            }
+        }
+        super::find_byte_swar(needle, &haystack[offset..]).map(|index| offset + index)
+    }
+
+    #[inline]
+    pub(super) fn unchanged_chunks_len(bytes: &[u8], ranges: &[(u8, u8)]) -> usize {
+        let mut offset = 0;
+        // SAFETY: SSE2 is enabled at compile time,
+        // and every load reads 16 bytes within `bytes`.
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let mut starts = [zero; super::MAX_RANGES];
+            let mut widths = [zero; super::MAX_RANGES];
+            for (i, &(start, end)) in ranges.iter().enumerate() {
+                starts[i] = _mm_set1_epi8(start as i8);
+                widths[i] = _mm_set1_epi8((end - start) as i8);
+            }
+            let (starts, widths) = (&starts[..ranges.len()], &widths[..ranges.len()]);
+            while offset + VECTOR_LEN <= bytes.len() {
+                let chunk = _mm_loadu_si128(bytes.as_ptr().add(offset) as *const __m128i);
+                // The high bit is set for non-ASCII bytes, and for bytes in a range below.
+                let mut matches = chunk;
+                for (&start, &width) in starts.iter().zip(widths) {
+                    // `byte - start <= end - start` with unsigned wrapping arithmetic.
+                    let shifted = _mm_sub_epi8(chunk, start);
+                    let in_range = _mm_cmpeq_epi8(_mm_min_epu8(shifted, width), shifted);
+                    matches = _mm_or_si128(matches, in_range);
+                }
+                if _mm_movemask_epi8(matches) != 0 {
+                    break;
+                }
+                offset += VECTOR_LEN;
+            }
+        }
+        offset + super::unchanged_words_len(&bytes[offset..], ranges)
+    }
+}
+
+/// The most ranges that `AsciiRanges` checks several bytes at a time.
+/// The sets of the `sets` module have up to twelve.
+const MAX_RANGES: usize = 12;
+
+/// The ASCII bytes of a `ByteSet` as inclusive ranges,
+/// which `ascii_unchanged_len` compares several bytes at a time.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AsciiRanges {
+    ranges: [(u8, u8); MAX_RANGES],
+    /// `None` if the set has more than `MAX_RANGES` ranges,
+    /// in which case bytes are checked one at a time.
+    len: Option<usize>,
+}
+
+impl AsciiRanges {
+    pub(crate) fn new(set: &ByteSet) -> Self {
+        let mut ranges = [(0, 0); MAX_RANGES];
+        let mut len = 0;
+        let mut mask = set.ascii_mask();
+        while mask != 0 {
+            if len == MAX_RANGES {
+                return AsciiRanges { ranges, len: None };
+            }
+            let start = mask.trailing_zeros();
+            let end = start + (!(mask >> start)).trailing_zeros();
+            ranges[len] = (start as u8, (end - 1) as u8);
+            len += 1;
+            if end == 128 {
+                break;
+            }
+            mask &= !0 << end;
+        }
+        AsciiRanges {
+            ranges,
+            len: Some(len),
+        }
+    }
+}
+
+/// Return the length of the run of ASCII bytes not in `set` at the start of `bytes`.
+///
+/// Runs are often short, so the first `HEAD_LEN` bytes are checked one at a time.
+/// Chunks of the following bytes are then compared with the ranges of `set`,
+/// and the chunk that contains the end of the run is checked one byte at a time.
+///
+/// The ranges are computed on first use and kept in `ranges` for later calls with `set`.
+#[inline]
+pub(crate) fn ascii_unchanged_len(
+    bytes: &[u8],
+    set: &ByteSet,
+    ranges: &mut Option<AsciiRanges>,
+) -> usize {
+    let changes = |byte: &u8| !byte.is_ascii() || set.contains(*byte);
+    let head = &bytes[..bytes.len().min(HEAD_LEN)];
+    if let Some(index) = head.iter().position(changes) {
+        return index;
+    }
+    if head.len() == bytes.len() {
+        return bytes.len();
+    }
+    let ranges = ranges.get_or_insert_with(|| AsciiRanges::new(set));
+    let offset = match ranges.len {
+        Some(len) => head.len() + unchanged_chunks_len(&bytes[head.len()..], &ranges.ranges[..len]),
+        None => head.len(),
+    };
+    bytes[offset..]
+        .iter()
+        .position(changes)
+        .map_or(bytes.len(), |index| offset + index)
+}
+
+/// How many bytes `ascii_unchanged_len` checks one at a time before checking chunks.
+const HEAD_LEN: usize = 32;
+
+/// Return the length of the chunks at the start of `bytes`
+/// that have no non-ASCII byte and no byte in `ranges`.
+#[inline]
+fn unchanged_chunks_len(bytes: &[u8], ranges: &[(u8, u8)]) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    {
+        sse2::unchanged_chunks_len(bytes, ranges)
+    }
+    #[cfg(not(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    )))]
+    {
+        unchanged_words_len(bytes, ranges)
+    }
+}
+
+/// The most ranges for which checking a word at a time is faster than a byte at a time.
+/// Each range costs a few operations per word: with the five ranges of `sets::QUERY`,
+/// the encoding benchmarks measured no difference.
+const MAX_WORD_RANGES: usize = 3;
+
+/// Return the length of the words at the start of `bytes`
+/// that have no non-ASCII byte and no byte in `ranges`,
+/// or 0 if `ranges` has more than `MAX_WORD_RANGES` ranges.
+#[inline]
+fn unchanged_words_len(bytes: &[u8], ranges: &[(u8, u8)]) -> usize {
+    if ranges.len() > MAX_WORD_RANGES {
+        return 0;
+    }
+    let mut offset = 0;
+    for chunk in bytes.chunks_exact(WORD_LEN) {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap());
+        if word & HIGH_BITS != 0
+            || ranges
+                .iter()
+                .any(|&(start, end)| has_ascii_byte_in_range(word, start, end))
+        {
+            break;
+        }
+        offset += WORD_LEN;
+    }
+    offset
+}
+
+/// Return whether `word`, which only has ASCII bytes, has a byte in `start..=end`.
+#[inline]
+fn has_ascii_byte_in_range(word: usize, start: u8, end: u8) -> bool {
+    // Neither can carry or borrow across bytes since all bytes are below 0x80:
+    // the high bit of each byte of `at_least_start` is set if the byte is at least `start`,
+    // and that of `at_most_end` if it is at most `end`.
+    let at_least_start = word + LOW_BITS * usize::from(0x80 - start);
+    let at_most_end = LOW_BITS * usize::from(0x80 + end) - word;
+    at_least_start & at_most_end & HIGH_BITS != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONTROLS;
+
+    #[test]
+    fn find_byte() {
+        let mut haystack = [b'a'; 100];
+        for &index in &[0, 1, 7, 8, 15, 16, 17, 31, 64, 99] {
+            haystack[index] = b'%';
+            assert_eq!(super::find_byte(b'%', &haystack), Some(index));
+            assert_eq!(find_byte_swar(b'%', &haystack), Some(index));
+            for start in 0..=index {
+                assert_eq!(
+                    super::find_byte(b'%', &haystack[start..]),
+                    Some(index - start)
+                );
+            }
+            haystack[index] = b'a';
+        }
+        assert_eq!(super::find_byte(b'%', &haystack), None);
+        assert_eq!(find_byte_swar(b'%', &haystack), None);
+        assert_eq!(super::find_byte(b'%', &[]), None);
+        // Bytes whose difference with the needle would carry.
+        assert_eq!(
+            find_byte_swar(0x80, &[0x7F, 0x81, 0x00, 0xFF, 0x80]),
+            Some(4)
+        );
+        assert_eq!(
+            find_byte_swar(0x00, &[1, 1, 1, 1, 1, 1, 1, 0x01, 0x00]),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn ascii_unchanged_len() {
+        let set = ByteSet::from_ascii_set(CONTROLS).add(b' ');
+        let mut ranges = None;
+        let mut bytes = [b'a'; 40];
+        assert_eq!(super::ascii_unchanged_len(&bytes, &set, &mut ranges), 40);
+        for &index in &[0, 5, 8, 9, 16, 39] {
+            for &byte in &[b' ', 0x00, 0x80, 0xFF] {
+                bytes[index] = byte;
+                assert_eq!(super::ascii_unchanged_len(&bytes, &set, &mut ranges), index);
+                let words_len = unchanged_words_len(&bytes, &[(0, 0x20), (0x7F, 0x7F)]);
+                assert_eq!(words_len, index / 8 * 8);
+            }
+            bytes[index] = b'a';
+        }
+        assert_eq!(super::ascii_unchanged_len(&[], &set, &mut ranges), 0);
+    }
+
+    #[test]
+    fn ascii_ranges() {
+        fn check(set: ByteSet, expected: &[(u8, u8)]) {
+            let ranges = AsciiRanges::new(&set);
+            assert_eq!(&ranges.ranges[..ranges.len.unwrap()], expected);
+        }
+        check(ByteSet::EMPTY, &[]);
+        check(ByteSet::NON_ASCII, &[]);
+        check(
+            ByteSet::from_ascii_set(CONTROLS),
+            &[(0, 0x1F), (0x7F, 0x7F)],
+        );
+        check(ByteSet::EMPTY.add_range(0..=0x7F), &[(0, 0x7F)]);
+        check(
+            ByteSet::EMPTY.add(b'a').add(b'c').add(0x7E),
+            &[(b'a', b'a'), (b'c', b'c'), (0x7E, 0x7E)],
+        );
+        let every_other: ByteSet = (0..0x80).step_by(2).collect();
+        assert_eq!(AsciiRanges::new(&every_other).len, None);
+    }
+
+    #[test]
+    fn has_ascii_byte_in_range() {
+        for byte in 0..0x80 {
+            let word = usize::from_ne_bytes([b'a'; WORD_LEN]) & !0xFF | byte;
+            for &(start, end) in &[
+                (0, 0),
+                (0, 0x20),
+                (0x22, 0x23),
+                (0x3C, 0x3C),
+                (0x7F, 0x7F),
+                (0, 0x7F),
+            ] {
+                let expected =
+                    (start..=end).contains(&(byte as u8)) || (start..=end).contains(&b'a');
+                assert_eq!(super::has_ascii_byte_in_range(word, start, end), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_unchanged_len_with_url_sets() {
+        use crate::sets::*;
+
+        let input = b"GET /api/v1/search?q=caf\xC3\xA9+au+lait&lang=fr-FR 200 \"Mozilla/5.0\"\n";
+        for set in &[
+            C0_CONTROL,
+            FRAGMENT,
+            QUERY,
+            SPECIAL_QUERY,
+            PATH,
+            USERINFO,
+            COMPONENT,
+            APPLICATION_X_WWW_FORM_URLENCODED,
+            PATH_SEGMENT,
+            SPECIAL_PATH_SEGMENT,
+            UNRESERVED,
+            PCHAR,
+            QUERY_OR_FRAGMENT,
+            crate::NON_ALPHANUMERIC,
+        ] {
+            let set = ByteSet::from_ascii_set(set);
+            let ranges = AsciiRanges::new(&set);
+            assert!(ranges.len.is_some());
+            for start in 0..input.len() {
+                let bytes = &input[start..];
+                let expected = bytes
+                    .iter()
+                    .position(|&byte| !byte.is_ascii() || set.contains(byte))
+                    .unwrap_or(bytes.len());
+                assert_eq!(super::ascii_unchanged_len(bytes, &set, &mut None), expected);
+                let ranges = &ranges.ranges[..ranges.len.unwrap()];
+                let words_len = unchanged_words_len(bytes, ranges);
+                if ranges.len() <= MAX_WORD_RANGES {
+                    assert_eq!(words_len, expected / WORD_LEN * WORD_LEN);
+                } else {
+                    assert_eq!(words_len, 0);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Percent-encode sets defined by the URL Standard and by RFC 3986.
+//!
+//! The [URL Standard](https://url.spec.whatwg.org/#percent-encoded-bytes) sets are those
+//! used by the `url` crate, and match what browsers do.
+//! They all include [`C0_CONTROL`], and from [`QUERY`] on each of
+//! [`PATH`], [`USERINFO`], [`COMPONENT`] and [`APPLICATION_X_WWW_FORM_URLENCODED`]
+//! is a superset of the previous one.
+//!
+//! The [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2) sets are complements:
+//! they contain every ASCII byte that is *not* allowed as-is in some part of a URI,
+//! so that encoding with them always produces a valid URI component.
+//! `%` is in all of them.
+//!
+//! # Examples
+//!
+//! ```
+//! use percent_encoding::sets::{PATH_SEGMENT, UNRESERVED};
+//! use percent_encoding::utf8_percent_encode;
+//!
+//! assert_eq!(utf8_percent_encode("a b/c?d", PATH_SEGMENT).to_string(), "a%20b%2Fc%3Fd");
+//! assert_eq!(utf8_percent_encode("a b/c~d", UNRESERVED).to_string(), "a%20b%2Fc~d");
+//! ```
+
+use crate::{AsciiSet, CONTROLS, NON_ALPHANUMERIC};
+
+/// The C0 control percent-encode set: 0x00 to 0x1F and 0x7F.
+///
+/// This is the same set as [`CONTROLS`].
+///
+/// <https://url.spec.whatwg.org/#c0-control-percent-encode-set>
+pub const C0_CONTROL: &AsciiSet = CONTROLS;
+
+/// The fragment percent-encode set, used for fragments.
+///
+/// <https://url.spec.whatwg.org/#fragment-percent-encode-set>
+pub const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
+
+/// The query percent-encode set, used for queries of non-special URLs.
+///
+/// <https://url.spec.whatwg.org/#query-percent-encode-set>
+pub const QUERY: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
+
+/// The special-query percent-encode set, used for queries of special URLs such as `http:`.
+///
+/// <https://url.spec.whatwg.org/#special-query-percent-encode-set>
+pub const SPECIAL_QUERY: &AsciiSet = &QUERY.add(b'\'');
+
+/// The path percent-encode set, used for paths that are not opaque.
+///
+/// <https://url.spec.whatwg.org/#path-percent-encode-set>
+pub const PATH: &AsciiSet = &QUERY.add(b'?').add(b'^').add(b'`').add(b'{').add(b'}');
+
+/// The userinfo percent-encode set, used for usernames and passwords.
+///
+/// <https://url.spec.whatwg.org/#userinfo-percent-encode-set>
+pub const USERINFO: &AsciiSet = &PATH
+    .add(b'/')
+    .add(b':')
+    .add(b';')
+    .add(b'=')
+    .add(b'@')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'|');
+
+/// The component percent-encode set, as used by JavaScript’s `encodeURIComponent()`.
+///
+/// <https://url.spec.whatwg.org/#component-percent-encode-set>
+pub const COMPONENT: &AsciiSet = &USERINFO.add(b'$').add(b'%').add(b'&').add(b'+').add(b',');
+
+/// The `application/x-www-form-urlencoded` percent-encode set.
+///
+/// Form serializers also replace spaces with `+`, which this set alone does not do.
+///
+/// <https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set>
+pub const APPLICATION_X_WWW_FORM_URLENCODED: &AsciiSet =
+    &COMPONENT.add(b'!').add(b'\'').add(b'(').add(b')').add(b'~');
+
+/// The path percent-encode set with `/` and `%`, for a single path segment
+/// of a non-special URL, such as given to `url::PathSegmentsMut::push`.
+///
+/// This set is not named by the URL Standard.
+pub const PATH_SEGMENT: &AsciiSet = &PATH.add(b'/').add(b'%');
+
+/// [`PATH_SEGMENT`] with `\`, for a single path segment of a special URL,
+/// where backslashes are path separators.
+///
+/// This set is not named by the URL Standard.
+pub const SPECIAL_PATH_SEGMENT: &AsciiSet = &PATH_SEGMENT.add(b'\\');
+
+/// Everything but RFC 3986 unreserved characters:
+/// ASCII letters and digits, `-`, `.`, `_` and `~`.
+///
+/// <https://www.rfc-editor.org/rfc/rfc3986#section-2.3>
+pub const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Everything but RFC 3986 `pchar` characters, which may appear in a path segment:
+/// unreserved characters, sub-delimiters `!$&'()*+,;=`, `:` and `@`.
+///
+/// <https://www.rfc-editor.org/rfc/rfc3986#section-3.3>
+pub const PCHAR: &AsciiSet = &UNRESERVED
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=')
+    .remove(b':')
+    .remove(b'@');
+
+/// Everything but the characters RFC 3986 allows in a query or a fragment:
+/// `pchar` characters, `/` and `?`.
+///
+/// <https://www.rfc-editor.org/rfc/rfc3986#section-3.4>
+pub const QUERY_OR_FRAGMENT: &AsciiSet = &PCHAR.remove(b'/').remove(b'?');
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(set: &AsciiSet) -> impl Iterator<Item = u8> + '_ {
+        (0..0x80).filter(move |&byte| set.contains(byte))
+    }
+
+    #[test]
+    fn whatwg_sets_are_nested() {
+        let sets = [
+            C0_CONTROL,
+            QUERY,
+            PATH,
+            USERINFO,
+            COMPONENT,
+            APPLICATION_X_WWW_FORM_URLENCODED,
+        ];
+        for pair in sets.windows(2) {
+            assert!(members(pair[0]).all(|byte| pair[1].contains(byte)));
+        }
+        assert!(members(FRAGMENT).all(|byte| PATH.contains(byte)));
+        assert!(members(QUERY).all(|byte| SPECIAL_QUERY.contains(byte)));
+        assert!(members(SPECIAL_QUERY).all(|byte| APPLICATION_X_WWW_FORM_URLENCODED.contains(byte)));
+        assert!(members(PATH).all(|byte| PATH_SEGMENT.contains(byte)));
+        assert!(members(PATH_SEGMENT).all(|byte| SPECIAL_PATH_SEGMENT.contains(byte)));
+    }
+
+    #[test]
+    fn application_x_www_form_urlencoded() {
+        // Everything but ASCII alphanumerics, `*`, `-`, `.` and `_`.
+        let expected = NON_ALPHANUMERIC
+            .remove(b'*')
+            .remove(b'-')
+            .remove(b'.')
+            .remove(b'_');
+        assert_eq!(*APPLICATION_X_WWW_FORM_URLENCODED, expected);
+    }
+
+    #[test]
+    fn rfc3986_sets() {
+        let allowed = |set: &AsciiSet, expected: &str| {
+            assert!((0x20..0x7F)
+                .filter(|&byte| !set.contains(byte))
+                .eq(expected.bytes()));
+        };
+        allowed(
+            UNRESERVED,
+            "-.0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz~",
+        );
+        allowed(
+            PCHAR,
+            "!$&'()*+,-.0123456789:;=@ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz~",
+        );
+        allowed(
+            QUERY_OR_FRAGMENT,
+            "!$&'()*+,-./0123456789:;=?@ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz~",
+        );
+        assert!(members(C0_CONTROL).all(|byte| QUERY_OR_FRAGMENT.contains(byte)));
+    }
+}
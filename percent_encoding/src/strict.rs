@@ -0,0 +1,230 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{decode_hex_pair, scan, ByteSet};
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+
+/// Percent-decode the given bytes, rejecting malformed escapes.
+///
+/// Unlike [`percent_decode`](crate::percent_decode),
+/// which leaves a `%` that is not followed by two hexadecimal digits as-is,
+/// this returns an error for the first such `%`.
+///
+/// The result borrows `input` when it contains no percent-encoded sequence.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::{percent_decode_strict, DecodeErrorKind};
+///
+/// assert_eq!(percent_decode_strict(b"foo%20bar%3f").unwrap(), &b"foo bar?"[..]);
+///
+/// let error = percent_decode_strict(b"100%zz").unwrap_err();
+/// assert_eq!(error.offset(), 3);
+/// assert_eq!(error.kind(), DecodeErrorKind::InvalidHexDigit);
+///
+/// let error = percent_decode_strict(b"100%").unwrap_err();
+/// assert_eq!(error.kind(), DecodeErrorKind::TruncatedEscape);
+/// ```
+pub fn percent_decode_strict(input: &[u8]) -> Result<Cow<'_, [u8]>, DecodeError> {
+    decode_strict(input, &ByteSet::EMPTY)
+}
+
+/// Percent-decode the given bytes like [`percent_decode_strict`],
+/// but leave percent-encoded sequences for bytes in `keep_encoded` as they are.
+///
+/// This can be used to make sure that decoding does not add separators to a string,
+/// for example that a decoded path has no `/` other than those of the original.
+///
+/// `keep_encoded` is a [`ByteSet`] rather than an [`AsciiSet`](crate::AsciiSet),
+/// since the latter always contains every non-ASCII byte
+/// and would keep all UTF-8 escapes encoded.
+///
+/// # Examples
+///
+/// ```
+/// use percent_encoding::{percent_decode_strict_except, ByteSet};
+///
+/// const SEPARATORS: &ByteSet = &ByteSet::EMPTY.add(b'/').add(b'\\');
+///
+/// let decoded = percent_decode_strict_except(b"/a%20b/..%2fetc", SEPARATORS).unwrap();
+/// assert_eq!(decoded, &b"/a b/..%2fetc"[..]);
+///
+/// let decoded = percent_decode_strict_except(b"caf%C3%A9%2F", SEPARATORS).unwrap();
+/// assert_eq!(decoded, "café%2F".as_bytes());
+/// ```
+pub fn percent_decode_strict_except<'a>(
+    input: &'a [u8],
+    keep_encoded: &ByteSet,
+) -> Result<Cow<'a, [u8]>, DecodeError> {
+    decode_strict(input, keep_encoded)
+}
+
+fn decode_strict<'a>(
+    input: &'a [u8],
+    keep_encoded: &ByteSet,
+) -> Result<Cow<'a, [u8]>, DecodeError> {
+    let mut decoded = Vec::new();
+    // The start of the input not yet copied to `decoded`.
+    let mut copied_up_to = 0;
+    let mut start = 0;
+    while let Some(index) = scan::find_byte(b'%', &input[start..]) {
+        let offset = start + index;
+        let byte = decode_hex_pair(&input[offset + 1..]).ok_or_else(|| DecodeError {
+            offset,
+            kind: if input.len() - offset < 3
+                && input[offset + 1..].iter().all(u8::is_ascii_hexdigit)
+            {
+                DecodeErrorKind::TruncatedEscape
+            } else {
+                DecodeErrorKind::InvalidHexDigit
+            },
+        })?;
+        start = offset + 3;
+        if !keep_encoded.contains(byte) {
+            decoded.extend_from_slice(&input[copied_up_to..offset]);
+            decoded.push(byte);
+            copied_up_to = start;
+        }
+    }
+    if copied_up_to == 0 {
+        return Ok(Cow::Borrowed(input));
+    }
+    decoded.extend_from_slice(&input[copied_up_to..]);
+    Ok(Cow::Owned(decoded))
+}
+
+/// The error type of [`percent_decode_strict`] and [`percent_decode_strict_except`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    offset: usize,
+    kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+    /// Return the offset in the input of the `%` starting the malformed escape.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Return what is wrong with the escape.
+    pub fn kind(&self) -> DecodeErrorKind {
+        self.kind
+    }
+}
+
+/// What is wrong with a malformed escape, as returned by [`DecodeError::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// One of the two bytes after `%` is not a hexadecimal digit.
+    InvalidHexDigit,
+    /// The input ends less than two bytes after `%`.
+    TruncatedEscape,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            DecodeErrorKind::InvalidHexDigit => "invalid hexadecimal digit in",
+            DecodeErrorKind::TruncatedEscape => "truncated",
+        };
+        write!(
+            f,
+            "{} percent-encoded sequence at offset {}",
+            description, self.offset
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONTROLS;
+    use alloc::string::ToString;
+
+    fn error(offset: usize, kind: DecodeErrorKind) -> Result<Cow<'static, [u8]>, DecodeError> {
+        Err(DecodeError { offset, kind })
+    }
+
+    #[test]
+    fn percent_decode_strict() {
+        use DecodeErrorKind::*;
+
+        assert_eq!(
+            super::percent_decode_strict(b""),
+            Ok(Cow::Borrowed(&b""[..]))
+        );
+        assert!(matches!(
+            super::percent_decode_strict(b"foo bar"),
+            Ok(Cow::Borrowed(b"foo bar"))
+        ));
+        assert_eq!(
+            super::percent_decode_strict(b"%41%2f%2F"),
+            Ok(b"A//".to_vec().into())
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"%"),
+            error(0, TruncatedEscape)
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"a%4"),
+            error(1, TruncatedEscape)
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"a%%41"),
+            error(1, InvalidHexDigit)
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"%41%4g"),
+            error(3, InvalidHexDigit)
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"%41%g"),
+            error(3, InvalidHexDigit)
+        );
+        assert_eq!(
+            super::percent_decode_strict(b"%20%zz%"),
+            error(3, InvalidHexDigit)
+        );
+    }
+
+    #[test]
+    fn percent_decode_strict_except() {
+        let separators = ByteSet::EMPTY.add(b'/');
+        let decode = |input| super::percent_decode_strict_except(input, &separators);
+        assert!(matches!(
+            decode(b"a%2Fb%2f"),
+            Ok(Cow::Borrowed(b"a%2Fb%2f"))
+        ));
+        assert_eq!(decode(b"%2e%2E%2F%41"), Ok(b"..%2FA".to_vec().into()));
+        assert_eq!(decode(b"%2F%"), error(3, DecodeErrorKind::TruncatedEscape));
+        assert_eq!(
+            decode(b"caf%C3%A9%2F"),
+            Ok("café%2F".as_bytes().to_vec().into())
+        );
+        assert_eq!(
+            super::percent_decode_strict_except(b"%00%01%C3%A9", &CONTROLS.into()),
+            Ok(Cow::Borrowed(&b"%00%01%C3%A9"[..]))
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            super::percent_decode_strict(b"ab%zz")
+                .unwrap_err()
+                .to_string(),
+            "invalid hexadecimal digit in percent-encoded sequence at offset 2"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+#[macro_use]
+extern crate bencher;
+
+use bencher::{black_box, Bencher};
+
+use percent_encoding::{percent_decode, percent_decode_into, percent_encode, percent_encode_to};
+use url::Url;
+
+const LOG_LINE: &str =
+    "GET /api/v1/search?q=caf\u{e9}+au+lait&lang=fr-FR 200 \"Mozilla/5.0 (X11; Linux x86_64)\"\n";
+
+fn long_input() -> String {
+    LOG_LINE.repeat(1000)
+}
+
+fn encoded_input() -> String {
+    let input = long_input();
+    percent_encode(input.as_bytes(), percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+fn encode_collect(bench: &mut Bencher) {
+    let input = long_input();
+    let set = percent_encoding::sets::QUERY;
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| percent_encode(black_box(input.as_bytes()), set).collect::<String>());
+}
+
+fn encode_to(bench: &mut Bencher) {
+    let input = long_input();
+    let set = percent_encoding::sets::QUERY;
+    let mut output = String::new();
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| {
+        output.clear();
+        percent_encode_to(black_box(input.as_bytes()), set, &mut output);
+    });
+}
+
+fn encode_mostly_unencoded(bench: &mut Bencher) {
+    let input = "0123456789abcdef".repeat(5000) + " ";
+    let set = percent_encoding::sets::QUERY;
+    let mut output = String::new();
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| {
+        output.clear();
+        percent_encode_to(black_box(input.as_bytes()), set, &mut output);
+    });
+}
+
+fn decode_collect(bench: &mut Bencher) {
+    let input = encoded_input();
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| percent_decode(black_box(input.as_bytes())).collect::<Vec<u8>>());
+}
+
+fn decode_into(bench: &mut Bencher) {
+    let input = encoded_input();
+    let mut output = Vec::new();
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| {
+        output.clear();
+        percent_decode_into(black_box(input.as_bytes()), &mut output);
+    });
+}
+
+fn decode_mostly_unencoded(bench: &mut Bencher) {
+    let input = long_input() + "%20";
+    let mut output = Vec::new();
+
+    bench.bytes = input.len() as u64;
+    bench.iter(|| {
+        output.clear();
+        percent_decode_into(black_box(input.as_bytes()), &mut output);
+    });
+}
+
+fn parse_encoded_query(bench: &mut Bencher) {
+    let url = format!("https://example.com/search?{}", long_input());
+
+    bench.bytes = url.len() as u64;
+    bench.iter(|| black_box(&url).parse::<Url>().unwrap());
+}
+
+benchmark_group!(
+    benches,
+    encode_collect,
+    encode_to,
+    encode_mostly_unencoded,
+    decode_collect,
+    decode_into,
+    decode_mostly_unencoded,
+    parse_encoded_query
+);
+benchmark_main!(benches);
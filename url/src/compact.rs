@@ -0,0 +1,589 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::host::{split_ipv6_zone, HostInternal};
+use crate::parser::{self, SchemeType};
+use crate::{Host, ParseError, Url};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::{cmp, fmt, hash, str};
+
+/// A read-only URL stored in a single allocation.
+///
+/// A `Url` keeps its serialization in a `String` next to component offsets,
+/// the port number and the parsed host,
+/// which adds up to 88 bytes on 64-bit platforms before the serialization itself.
+/// `CompactUrl` is two words: a pointer to a boxed string slice that starts
+/// with a small header encoding the component offsets, followed by the serialization.
+/// The header takes between 6 and 36 bytes depending on the length of the URL,
+/// which makes this type suitable for keeping very large numbers of URLs in memory.
+///
+/// `CompactUrl` offers the same read-only getters as `Url`.
+/// Convert it back to a `Url` to modify it.
+/// IP addresses and port numbers are not stored separately,
+/// they are parsed back from the serialization when requested.
+///
+/// Like `Url`, values compare and hash like their serialization.
+///
+/// # Examples
+///
+/// ```rust
+/// use url::{CompactUrl, Url};
+/// # use url::ParseError;
+///
+/// # fn run() -> Result<(), ParseError> {
+/// let url = CompactUrl::parse("https://example.net:8080/a/b?c=d#e")?;
+/// assert_eq!(url.as_str(), "https://example.net:8080/a/b?c=d#e");
+/// assert_eq!(url.host_str(), Some("example.net"));
+/// assert_eq!(url.port(), Some(8080));
+/// assert_eq!(url.path(), "/a/b");
+///
+/// let mut url = Url::from(url);
+/// url.set_fragment(None);
+/// let url = CompactUrl::from(url);
+/// assert_eq!(url.as_str(), "https://example.net:8080/a/b?c=d");
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct CompactUrl {
+    /// Syntax:
+    ///
+    ///   data = flags offset{5,7} serialization
+    ///
+    /// All header bytes are ASCII so that `data` is a valid `str`.
+    /// `flags` stores the number of base-128 digits per offset in its lower 3 bits,
+    /// the host kind in the next 2 bits, then whether a query and a fragment are present.
+    /// Domains and opaque hosts share a host kind, they are told apart by the scheme.
+    /// Offsets follow in the order of the corresponding `Url` fields, most significant digit first.
+    data: Box<str>,
+}
+
+const WIDTH_MASK: u8 = 0b111;
+const HOST_SHIFT: u8 = 3;
+const HAS_QUERY: u8 = 1 << 5;
+const HAS_FRAGMENT: u8 = 1 << 6;
+
+const HOST_NONE: u8 = 0;
+const HOST_DOMAIN: u8 = 1;
+const HOST_IPV4: u8 = 2;
+const HOST_IPV6: u8 = 3;
+
+/// Offsets decoded from the header, relative to the start of the serialization.
+struct Offsets {
+    header_len: usize,
+    host: u8,
+    scheme_end: usize,
+    username_end: usize,
+    host_start: usize,
+    host_end: usize,
+    path_start: usize,
+    query_start: Option<usize>,
+    fragment_start: Option<usize>,
+}
+
+impl CompactUrl {
+    /// Parse an absolute URL from a string.
+    ///
+    /// This is equivalent to `Url::parse(input).map(CompactUrl::from)`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Url::parse(input).map(Self::from)
+    }
+
+    #[inline]
+    fn header_len(&self) -> usize {
+        let flags = self.data.as_bytes()[0];
+        let count = 5 + (flags & HAS_QUERY != 0) as usize + (flags & HAS_FRAGMENT != 0) as usize;
+        1 + (flags & WIDTH_MASK) as usize * count
+    }
+
+    fn offsets(&self) -> Offsets {
+        let bytes = self.data.as_bytes();
+        let flags = bytes[0];
+        let width = (flags & WIDTH_MASK) as usize;
+        let mut position = 1;
+        let mut next = || {
+            let digits = &bytes[position..position + width];
+            position += width;
+            digits
+                .iter()
+                .fold(0, |offset, &digit| (offset << 7) | digit as usize)
+        };
+        let scheme_end = next();
+        let username_end = next();
+        let host_start = next();
+        let host_end = next();
+        let path_start = next();
+        let query_start = if flags & HAS_QUERY != 0 {
+            Some(next())
+        } else {
+            None
+        };
+        let fragment_start = if flags & HAS_FRAGMENT != 0 {
+            Some(next())
+        } else {
+            None
+        };
+        Offsets {
+            header_len: position,
+            host: (flags >> HOST_SHIFT) & 0b11,
+            scheme_end,
+            username_end,
+            host_start,
+            host_end,
+            path_start,
+            query_start,
+            fragment_start,
+        }
+    }
+
+    /// Return the serialization of this URL.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.data[self.header_len()..]
+    }
+
+    /// Return the scheme of this URL, lower-cased, as an ASCII string without the ':' delimiter.
+    ///
+    /// See [`Url::scheme`].
+    pub fn scheme(&self) -> &str {
+        let offsets = self.offsets();
+        &self.as_str()[..offsets.scheme_end]
+    }
+
+    /// Return whether the URL is special (has a special scheme).
+    ///
+    /// See [`Url::is_special`].
+    pub fn is_special(&self) -> bool {
+        SchemeType::from(self.scheme()).is_special()
+    }
+
+    /// Return whether the URL has an 'authority'.
+    ///
+    /// See [`Url::has_authority`].
+    pub fn has_authority(&self) -> bool {
+        let offsets = self.offsets();
+        self.as_str()[offsets.scheme_end..].starts_with("://")
+    }
+
+    /// Return the authority of this URL as an ASCII string.
+    ///
+    /// See [`Url::authority`].
+    pub fn authority(&self) -> &str {
+        let offsets = self.offsets();
+        let authority_start = offsets.scheme_end + "://".len();
+        if self.has_authority() && offsets.path_start > authority_start {
+            &self.as_str()[authority_start..offsets.path_start]
+        } else {
+            ""
+        }
+    }
+
+    /// Return whether this URL is a cannot-be-a-base URL.
+    ///
+    /// See [`Url::cannot_be_a_base`].
+    pub fn cannot_be_a_base(&self) -> bool {
+        let offsets = self.offsets();
+        !self.as_str()[offsets.scheme_end + 1..].starts_with('/')
+    }
+
+    /// Return the username for this URL as a percent-encoded ASCII string.
+    ///
+    /// See [`Url::username`].
+    pub fn username(&self) -> &str {
+        let offsets = self.offsets();
+        let username_start = offsets.scheme_end + "://".len();
+        if self.has_authority() && offsets.username_end > username_start {
+            &self.as_str()[username_start..offsets.username_end]
+        } else {
+            ""
+        }
+    }
+
+    /// Return the password for this URL, if any, as a percent-encoded ASCII string.
+    ///
+    /// See [`Url::password`].
+    pub fn password(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        let serialization = self.as_str();
+        if self.has_authority() && serialization[offsets.username_end..].starts_with(':') {
+            Some(&serialization[offsets.username_end + 1..offsets.host_start - 1])
+        } else {
+            None
+        }
+    }
+
+    /// Equivalent to `url.host().is_some()`.
+    pub fn has_host(&self) -> bool {
+        self.offsets().host != HOST_NONE
+    }
+
+    /// Return the string representation of the host for this URL, if any.
+    ///
+    /// See [`Url::host_str`].
+    pub fn host_str(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        if offsets.host == HOST_NONE {
+            None
+        } else {
+            Some(&self.as_str()[offsets.host_start..offsets.host_end])
+        }
+    }
+
+    /// Return the parsed representation of the host for this URL.
+    ///
+    /// See [`Url::host`].
+    pub fn host(&self) -> Option<Host<&str>> {
+        let offsets = self.offsets();
+        let host = &self.as_str()[offsets.host_start..offsets.host_end];
+        match offsets.host {
+            HOST_DOMAIN => Some(Host::Domain(host)),
+            HOST_IPV4 => Some(Host::Ipv4(host.parse().expect("serialized IPv4 address"))),
+            HOST_IPV6 => Some(Host::Ipv6(
+                split_ipv6_zone(&host[1..host.len() - 1])
+                    .0
+                    .parse()
+                    .expect("serialized IPv6 address"),
+            )),
+            _ => None,
+        }
+    }
+
+    /// If this URL has a host and it is a domain name (not an IP address), return it.
+    ///
+    /// See [`Url::domain`].
+    pub fn domain(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        if offsets.host == HOST_DOMAIN && self.is_special() {
+            Some(&self.as_str()[offsets.host_start..offsets.host_end])
+        } else {
+            None
+        }
+    }
+
+    /// If this URL is not special and has a host that is not an IP address, return it.
+    ///
+    /// See [`Url::opaque_host`].
+    pub fn opaque_host(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        if offsets.host == HOST_DOMAIN && !self.is_special() {
+            Some(&self.as_str()[offsets.host_start..offsets.host_end])
+        } else {
+            None
+        }
+    }
+
+    /// Return the port number for this URL, if any.
+    ///
+    /// See [`Url::port`].
+    pub fn port(&self) -> Option<u16> {
+        let offsets = self.offsets();
+        // Without an authority, `path_start` is after `host_end` for paths starting with `/.//`
+        if self.has_authority() && offsets.path_start > offsets.host_end {
+            let port = &self.as_str()[offsets.host_end + 1..offsets.path_start];
+            Some(port.parse().expect("serialized port number"))
+        } else {
+            None
+        }
+    }
+
+    /// Return the port number for this URL, or the default port number if it is known.
+    ///
+    /// See [`Url::port_or_known_default`].
+    pub fn port_or_known_default(&self) -> Option<u16> {
+        self.port().or_else(|| parser::default_port(self.scheme()))
+    }
+
+    /// Return the path for this URL, as a percent-encoded ASCII string.
+    ///
+    /// See [`Url::path`].
+    pub fn path(&self) -> &str {
+        let offsets = self.offsets();
+        let serialization = self.as_str();
+        match offsets.query_start.or(offsets.fragment_start) {
+            Some(next_component_start) => &serialization[offsets.path_start..next_component_start],
+            None => &serialization[offsets.path_start..],
+        }
+    }
+
+    /// Unless this URL is cannot-be-a-base,
+    /// return an iterator of '/' slash-separated path segments,
+    /// each as a percent-encoded ASCII string.
+    ///
+    /// See [`Url::path_segments`].
+    pub fn path_segments(&self) -> Option<str::Split<'_, char>> {
+        self.path()
+            .strip_prefix('/')
+            .map(|remainder| remainder.split('/'))
+    }
+
+    /// Return this URL’s query string, if any, as a percent-encoded ASCII string.
+    ///
+    /// See [`Url::query`].
+    pub fn query(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        let serialization = self.as_str();
+        let query_start = offsets.query_start?;
+        match offsets.fragment_start {
+            Some(fragment_start) => Some(&serialization[query_start + 1..fragment_start]),
+            None => Some(&serialization[query_start + 1..]),
+        }
+    }
+
+    /// Parse the URL’s query string, if any, as `application/x-www-form-urlencoded`
+    /// and return an iterator of (key, value) pairs.
+    ///
+    /// See [`Url::query_pairs`].
+    #[inline]
+    pub fn query_pairs(&self) -> form_urlencoded::Parse<'_> {
+        form_urlencoded::parse(self.query().unwrap_or("").as_bytes())
+    }
+
+    /// Return this URL’s fragment identifier, if any.
+    ///
+    /// See [`Url::fragment`].
+    pub fn fragment(&self) -> Option<&str> {
+        let offsets = self.offsets();
+        offsets
+            .fragment_start
+            .map(|start| &self.as_str()[start + 1..])
+    }
+
+    /// Convert to a `Url`, copying the serialization.
+    pub fn to_url(&self) -> Url {
+        let (offsets, host, port) = self.url_fields();
+        url_from_parts(String::from(self.as_str()), offsets, host, port)
+    }
+
+    fn url_fields(&self) -> (Offsets, HostInternal, Option<u16>) {
+        let host = match self.host() {
+            None => HostInternal::None,
+            Some(Host::Domain(_)) => {
+                HostInternal::Domain.for_scheme(SchemeType::from(self.scheme()))
+            }
+            Some(Host::Ipv4(address)) => HostInternal::Ipv4(address),
+            Some(Host::Ipv6(address)) => HostInternal::Ipv6(address),
+        };
+        (self.offsets(), host, self.port())
+    }
+}
+
+fn url_from_parts(
+    serialization: String,
+    offsets: Offsets,
+    host: HostInternal,
+    port: Option<u16>,
+) -> Url {
+    // Offsets were taken from a `Url`, so they fit in a `u32`
+    let u32_offset = |offset: usize| offset as u32;
+    Url {
+        serialization,
+        scheme_end: u32_offset(offsets.scheme_end),
+        username_end: u32_offset(offsets.username_end),
+        host_start: u32_offset(offsets.host_start),
+        host_end: u32_offset(offsets.host_end),
+        host,
+        port,
+        path_start: u32_offset(offsets.path_start),
+        query_start: offsets.query_start.map(u32_offset),
+        fragment_start: offsets.fragment_start.map(u32_offset),
+    }
+}
+
+impl From<&Url> for CompactUrl {
+    fn from(url: &Url) -> Self {
+        let len = url.serialization.len();
+        let mut width = 1;
+        while width < 5 && len >> (7 * width) != 0 {
+            width += 1;
+        }
+        let host = match url.host {
+            HostInternal::None => HOST_NONE,
+            HostInternal::Domain | HostInternal::Opaque => HOST_DOMAIN,
+            HostInternal::Ipv4(_) => HOST_IPV4,
+            HostInternal::Ipv6(_) => HOST_IPV6,
+        };
+        let mut flags = width as u8 | (host << HOST_SHIFT);
+        if url.query_start.is_some() {
+            flags |= HAS_QUERY;
+        }
+        if url.fragment_start.is_some() {
+            flags |= HAS_FRAGMENT;
+        }
+        let offsets = [
+            Some(url.scheme_end),
+            Some(url.username_end),
+            Some(url.host_start),
+            Some(url.host_end),
+            Some(url.path_start),
+            url.query_start,
+            url.fragment_start,
+        ];
+        let mut data = String::with_capacity(1 + offsets.len() * width + len);
+        data.push(flags as char);
+        for offset in offsets.iter().flatten() {
+            for digit in (0..width).rev() {
+                data.push(((offset >> (7 * digit)) & 0x7F) as u8 as char);
+            }
+        }
+        data.push_str(&url.serialization);
+        CompactUrl {
+            data: data.into_boxed_str(),
+        }
+    }
+}
+
+impl From<Url> for CompactUrl {
+    #[inline]
+    fn from(url: Url) -> Self {
+        Self::from(&url)
+    }
+}
+
+impl From<&CompactUrl> for Url {
+    #[inline]
+    fn from(url: &CompactUrl) -> Self {
+        url.to_url()
+    }
+}
+
+impl From<CompactUrl> for Url {
+    /// Reuse the allocation of the `CompactUrl`, removing the header in place.
+    fn from(url: CompactUrl) -> Self {
+        let (offsets, host, port) = url.url_fields();
+        let mut serialization = String::from(url.data);
+        serialization.drain(..offsets.header_len);
+        url_from_parts(serialization, offsets, host, port)
+    }
+}
+
+/// Parse a string as an URL, without a base URL or encoding override.
+impl str::FromStr for CompactUrl {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Self::parse(input)
+    }
+}
+
+/// Display the serialization of this URL.
+impl fmt::Display for CompactUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), formatter)
+    }
+}
+
+/// Debug the serialization of this URL.
+impl fmt::Debug for CompactUrl {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CompactUrl")
+            .field("scheme", &self.scheme())
+            .field("cannot_be_a_base", &self.cannot_be_a_base())
+            .field("username", &self.username())
+            .field("password", &self.password())
+            .field("host", &self.host())
+            .field("port", &self.port())
+            .field("path", &self.path())
+            .field("query", &self.query())
+            .field("fragment", &self.fragment())
+            .finish()
+    }
+}
+
+/// URLs compare like their serialization.
+impl Eq for CompactUrl {}
+
+/// URLs compare like their serialization.
+impl PartialEq for CompactUrl {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<Url> for CompactUrl {
+    #[inline]
+    fn eq(&self, other: &Url) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<CompactUrl> for Url {
+    #[inline]
+    fn eq(&self, other: &CompactUrl) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+/// URLs compare like their serialization.
+impl Ord for CompactUrl {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialOrd for CompactUrl {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// URLs hash like their serialization.
+impl hash::Hash for CompactUrl {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(self.as_str(), state)
+    }
+}
+
+/// Return the serialization of this URL.
+impl AsRef<str> for CompactUrl {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Serializes this URL into a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CompactUrl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes this URL from a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CompactUrl {
+    fn deserialize<D>(deserializer: D) -> Result<CompactUrl, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <Url as serde::Deserialize>::deserialize(deserializer).map(CompactUrl::from)
+    }
+}
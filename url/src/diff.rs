@@ -0,0 +1,498 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{ParseError, SetterError, Url};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A component that changed between two URLs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Change<T> {
+    /// The value in the first URL.
+    pub old: T,
+    /// The value in the second URL.
+    pub new: T,
+}
+
+/// A change to the path segments of a URL, as returned in [`UrlDiff::path_segments`].
+///
+/// Segments are percent-encoded.
+/// Indices are those of the segments when the change is applied,
+/// after the previous changes of the list.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegmentChange {
+    /// `segment` was inserted at `index`.
+    Added { index: usize, segment: String },
+    /// `segment` was removed from `index`.
+    Removed { index: usize, segment: String },
+    /// The segment at `index` was replaced.
+    Changed {
+        index: usize,
+        old: String,
+        new: String,
+    },
+}
+
+/// A change to the `application/x-www-form-urlencoded` pairs of a query,
+/// as returned in [`UrlDiff::query_pairs`].
+///
+/// Names and values are decoded, like those of [`Url::query_pairs`].
+/// Pairs with the same name are matched in order of appearance:
+/// `index` is the position of a pair among those named `name`,
+/// in the new query for added pairs and in the old query otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QueryPairChange {
+    /// A pair was added.
+    Added {
+        name: String,
+        index: usize,
+        value: String,
+    },
+    /// A pair was removed.
+    Removed {
+        name: String,
+        index: usize,
+        value: String,
+    },
+    /// The value of a pair changed.
+    Changed {
+        name: String,
+        index: usize,
+        old: String,
+        new: String,
+    },
+}
+
+/// The differences between two URLs, as returned by [`Url::diff`].
+///
+/// Each component is `None` if it is the same in both URLs.
+/// Components are compared in their serialized, percent-encoded form,
+/// except for query pairs which are decoded.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct UrlDiff {
+    /// The scheme.
+    pub scheme: Option<Change<String>>,
+    /// The username, percent-encoded.
+    pub username: Option<Change<String>>,
+    /// The password, percent-encoded.
+    pub password: Option<Change<Option<String>>>,
+    /// The host, as returned by [`Url::host_str`],
+    /// or `Some("")` for an empty host such as in `file:///` or `foo:///`.
+    pub host: Option<Change<Option<String>>>,
+    /// The port, as returned by [`Url::port`].
+    pub port: Option<Change<Option<u16>>>,
+    /// The path, percent-encoded.
+    pub path: Option<Change<String>>,
+    /// The changes to the path segments,
+    /// empty if the paths are the same or if either URL is cannot-be-a-base.
+    pub path_segments: Vec<PathSegmentChange>,
+    /// The query string, percent-encoded.
+    pub query: Option<Change<Option<String>>>,
+    /// The changes to the query pairs, empty if the queries are the same.
+    pub query_pairs: Vec<QueryPairChange>,
+    /// The fragment identifier, percent-encoded.
+    pub fragment: Option<Change<Option<String>>>,
+}
+
+#[derive(Clone, Copy)]
+enum Component {
+    Scheme,
+    Host,
+    Port,
+    Username,
+    Password,
+}
+
+fn change<T: PartialEq>(old: T, new: T) -> Option<Change<T>> {
+    if old == new {
+        None
+    } else {
+        Some(Change { old, new })
+    }
+}
+
+fn owned(value: Option<&str>) -> Option<String> {
+    value.map(ToOwned::to_owned)
+}
+
+fn host(url: &Url) -> Option<String> {
+    if url.has_authority() {
+        Some(url.host_str().unwrap_or_default().to_owned())
+    } else {
+        None
+    }
+}
+
+impl UrlDiff {
+    pub(crate) fn new(old: &Url, new: &Url) -> Self {
+        if old.as_str() == new.as_str() {
+            return Self::default();
+        }
+        let mut diff = UrlDiff {
+            scheme: change(old.scheme(), new.scheme()).map(Change::into_owned),
+            username: change(old.username(), new.username()).map(Change::into_owned),
+            password: change(owned(old.password()), owned(new.password())),
+            host: change(host(old), host(new)),
+            port: change(old.port(), new.port()),
+            path: change(old.path(), new.path()).map(Change::into_owned),
+            path_segments: Vec::new(),
+            query: change(owned(old.query()), owned(new.query())),
+            query_pairs: Vec::new(),
+            fragment: change(owned(old.fragment()), owned(new.fragment())),
+        };
+        if diff.path.is_some() {
+            if let (Some(old), Some(new)) = (old.path_segments(), new.path_segments()) {
+                diff.path_segments =
+                    diff_segments(&old.collect::<Vec<_>>(), &new.collect::<Vec<_>>());
+            }
+        }
+        if diff.query.is_some() {
+            diff.query_pairs = diff_query_pairs(old, new);
+        }
+        diff
+    }
+
+    /// Return whether the two URLs are the same.
+    pub fn is_empty(&self) -> bool {
+        self.scheme.is_none()
+            && self.username.is_none()
+            && self.password.is_none()
+            && self.host.is_none()
+            && self.port.is_none()
+            && self.path.is_none()
+            && self.query.is_none()
+            && self.fragment.is_none()
+    }
+
+    /// Apply these changes to `url` through its setters.
+    ///
+    /// Applying a diff to the first of the two URLs it was computed from
+    /// gives the second one.
+    /// When applied to another URL, components that changed are set to their new value,
+    /// except for the path and the query when they differ from the old value:
+    /// path segment changes and query pair changes are then replayed on the existing ones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let old = Url::parse("https://example.net/a/b?c=d&e=f")?;
+    /// let new = Url::parse("https://example.net/a/x?c=d&e=g")?;
+    /// let diff = old.diff(&new);
+    ///
+    /// let mut url = Url::parse("https://example.net/a/b/c?c=d&e=f&h=i")?;
+    /// diff.apply(&mut url).unwrap();
+    /// assert_eq!(url.as_str(), "https://example.net/a/x/c?c=d&e=g&h=i");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Setters are called in the order scheme, host, port, username, password,
+    /// and those that fail are retried after the others,
+    /// for example so that a host is set before a port.
+    /// If a component still can not be set, a [`SetterError`] variant is returned
+    /// and `url` is unchanged.
+    pub fn apply(&self, url: &mut Url) -> Result<(), SetterError> {
+        let mut result = url.clone();
+        let mut pending = [
+            Component::Scheme,
+            Component::Host,
+            Component::Port,
+            Component::Username,
+            Component::Password,
+        ]
+        .to_vec();
+        while !pending.is_empty() {
+            let mut error = None;
+            let count = pending.len();
+            pending.retain(
+                |&component| match self.apply_component(&mut result, component) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        error = Some(err);
+                        true
+                    }
+                },
+            );
+            if let Some(err) = error.filter(|_| pending.len() == count) {
+                return Err(err);
+            }
+        }
+        if let Some(path) = &self.path {
+            self.apply_path(&mut result, path);
+            // Paths can not change between opaque and hierarchical.
+            if result.cannot_be_a_base() == path.new.starts_with('/') {
+                return Err(SetterError::CannotBeABase);
+            }
+        }
+        if let Some(query) = &self.query {
+            self.apply_query(&mut result, query);
+        }
+        if let Some(fragment) = &self.fragment {
+            result.set_fragment(fragment.new.as_deref());
+        }
+        *url = result;
+        Ok(())
+    }
+
+    fn apply_component(&self, url: &mut Url, component: Component) -> Result<(), SetterError> {
+        match component {
+            Component::Scheme => match &self.scheme {
+                Some(scheme) if url.scheme() != scheme.new => url.try_set_scheme(&scheme.new),
+                _ => Ok(()),
+            },
+            Component::Host => match &self.host {
+                Some(change) if host(url) != change.new => {
+                    let new = change.new.as_deref();
+                    url.set_host(new).map_err(|err| match err {
+                        ParseError::SetHostOnCannotBeABaseUrl => SetterError::CannotBeABase,
+                        _ => SetterError::InvalidHost,
+                    })?;
+                    // A host is only removed once the credentials and port are removed.
+                    if host(url) != change.new {
+                        return Err(SetterError::NoHost);
+                    }
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            Component::Port => match &self.port {
+                Some(port) if url.port() != port.new => url.try_set_port(port.new),
+                _ => Ok(()),
+            },
+            Component::Username => match &self.username {
+                Some(username) if url.username() != username.new => {
+                    url.try_set_username(&username.new)
+                }
+                _ => Ok(()),
+            },
+            Component::Password => match &self.password {
+                Some(password) if url.password() != password.new.as_deref() => {
+                    url.try_set_password(password.new.as_deref())
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn apply_path(&self, url: &mut Url, path: &Change<String>) {
+        if url.path() == path.old || self.path_segments.is_empty() {
+            return url.set_path(&path.new);
+        }
+        let mut segments: Vec<String> = match url.path_segments() {
+            Some(segments) => segments.map(ToOwned::to_owned).collect(),
+            None => return url.set_path(&path.new),
+        };
+        for change in &self.path_segments {
+            match change {
+                PathSegmentChange::Added { index, segment } => {
+                    segments.insert((*index).min(segments.len()), segment.clone())
+                }
+                PathSegmentChange::Removed { index, .. } => {
+                    if *index < segments.len() {
+                        segments.remove(*index);
+                    }
+                }
+                PathSegmentChange::Changed { index, new, .. } => match segments.get_mut(*index) {
+                    Some(segment) => *segment = new.clone(),
+                    None => segments.push(new.clone()),
+                },
+            }
+        }
+        let mut new_path = String::new();
+        for segment in &segments {
+            new_path.push('/');
+            new_path.push_str(segment);
+        }
+        url.set_path(&new_path)
+    }
+
+    fn apply_query(&self, url: &mut Url, query: &Change<Option<String>>) {
+        if url.query() == query.old.as_deref() || query.new.is_none() || self.query_pairs.is_empty()
+        {
+            return url.set_query(query.new.as_deref());
+        }
+        // Splice the changes into the raw query string,
+        // so that unchanged pairs keep their bytes, such as `%20` instead of `+`.
+        let mut pairs: Vec<RawPair<'_>> = url
+            .query()
+            .filter(|query| !query.is_empty())
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .map(|raw| RawPair {
+                raw: Cow::Borrowed(raw),
+                pair: form_urlencoded::parse(raw.as_bytes())
+                    .next()
+                    .map(|(name, value)| (name.into_owned(), value.into_owned())),
+                removed: false,
+            })
+            .collect();
+        // Return the position of the `index`-th pair named `name` if its value is `value`,
+        // or else of the first pair with that name and value.
+        // Removed pairs are still counted, since indices are those of the old query.
+        let find = |pairs: &[RawPair<'_>], name: &str, index: usize, value: &str| {
+            let mut named = pairs
+                .iter()
+                .enumerate()
+                .filter(|(_, raw)| matches!(&raw.pair, Some((n, _)) if n == name));
+            let is_match =
+                |raw: &RawPair<'_>| !raw.removed && matches!(&raw.pair, Some((_, v)) if v == value);
+            match named.clone().nth(index) {
+                Some((i, raw)) if is_match(raw) => Some(i),
+                _ => named.find(|(_, raw)| is_match(raw)).map(|(i, _)| i),
+            }
+        };
+        for change in &self.query_pairs {
+            match change {
+                QueryPairChange::Added { name, value, .. } => {
+                    pairs.push(RawPair::encode(name, value))
+                }
+                QueryPairChange::Removed { name, index, value } => {
+                    if let Some(i) = find(&pairs, name, *index, value) {
+                        pairs[i].removed = true;
+                    }
+                }
+                QueryPairChange::Changed {
+                    name,
+                    index,
+                    old,
+                    new,
+                } => match find(&pairs, name, *index, old) {
+                    Some(i) => pairs[i] = RawPair::encode(name, new),
+                    None => pairs.push(RawPair::encode(name, new)),
+                },
+            }
+        }
+        let raw_pairs: Vec<&str> = pairs
+            .iter()
+            .filter(|raw| !raw.removed)
+            .map(|raw| &*raw.raw)
+            .collect();
+        url.set_query(Some(&raw_pairs.join("&")));
+    }
+}
+
+/// A raw `&`-separated part of a query, with its decoded pair if not empty.
+struct RawPair<'a> {
+    raw: Cow<'a, str>,
+    pair: Option<(String, String)>,
+    removed: bool,
+}
+
+impl RawPair<'_> {
+    fn encode(name: &str, value: &str) -> Self {
+        let raw = form_urlencoded::Serializer::new(String::new())
+            .append_pair(name, value)
+            .finish();
+        RawPair {
+            raw: Cow::Owned(raw),
+            pair: Some((name.to_owned(), value.to_owned())),
+            removed: false,
+        }
+    }
+}
+
+impl Change<&str> {
+    fn into_owned(self) -> Change<String> {
+        Change {
+            old: self.old.to_owned(),
+            new: self.new.to_owned(),
+        }
+    }
+}
+
+/// Return the changes from `old` to `new`,
+/// replacing segments between their common prefix and suffix.
+fn diff_segments(old: &[&str], new: &[&str]) -> Vec<PathSegmentChange> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+    let mut changes = Vec::new();
+    for (i, (old, new)) in old.iter().zip(new).enumerate() {
+        changes.push(PathSegmentChange::Changed {
+            index: prefix + i,
+            old: (*old).to_owned(),
+            new: (*new).to_owned(),
+        });
+    }
+    let index = prefix + old.len().min(new.len());
+    for segment in old.iter().skip(new.len()) {
+        changes.push(PathSegmentChange::Removed {
+            index,
+            segment: (*segment).to_owned(),
+        });
+    }
+    for (i, segment) in new.iter().skip(old.len()).enumerate() {
+        changes.push(PathSegmentChange::Added {
+            index: index + i,
+            segment: (*segment).to_owned(),
+        });
+    }
+    changes
+}
+
+fn diff_query_pairs(old: &Url, new: &Url) -> Vec<QueryPairChange> {
+    let old: Vec<(String, String)> = old.query_pairs().into_owned().collect();
+    let new: Vec<(String, String)> = new.query_pairs().into_owned().collect();
+    let mut names: Vec<&str> = Vec::new();
+    for (name, _) in old.iter().chain(&new) {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    let mut changes = Vec::new();
+    for name in names {
+        let values = |pairs: &'_ [(String, String)]| -> Vec<String> {
+            pairs
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, v)| v.clone())
+                .collect()
+        };
+        let (old, new) = (values(&old), values(&new));
+        for (index, (old, new)) in old.iter().zip(&new).enumerate() {
+            if old != new {
+                changes.push(QueryPairChange::Changed {
+                    name: name.to_owned(),
+                    index,
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+        for (index, value) in old.iter().enumerate().skip(new.len()) {
+            changes.push(QueryPairChange::Removed {
+                name: name.to_owned(),
+                index,
+                value: value.clone(),
+            });
+        }
+        for (index, value) in new.iter().enumerate().skip(old.len()) {
+            changes.push(QueryPairChange::Added {
+                name: name.to_owned(),
+                index,
+                value: value.clone(),
+            });
+        }
+    }
+    changes
+}
@@ -18,7 +18,7 @@ use percent_encoding::{percent_decode, utf8_percent_encode, CONTROLS};
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
 
-use crate::parser::{ParseError, ParseResult};
+use crate::parser::{ParseError, ParseResult, SchemeType};
 
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -27,6 +27,21 @@ pub(crate) enum HostInternal {
     Domain,
     Ipv4(Ipv4Addr),
     Ipv6(Ipv6Addr),
+    /// The host of a non-special URL, which is not necessarily a DNS domain.
+    ///
+    /// This is the last variant so that `Url::serialize_internal` keeps the variant indices
+    /// of earlier versions.
+    Opaque,
+}
+
+impl HostInternal {
+    /// Return `Opaque` instead of `Domain` for the host of a non-special URL.
+    pub(crate) fn for_scheme(self, scheme_type: SchemeType) -> Self {
+        match self {
+            Self::Domain if !scheme_type.is_special() => Self::Opaque,
+            host => host,
+        }
+    }
 }
 
 impl From<Host<Cow<'_, str>>> for HostInternal {
@@ -48,6 +63,10 @@ pub enum Host<S = String> {
     /// Non-ASCII labels are encoded in punycode per IDNA if this is the host of
     /// a special URL, or percent encoded for non-special URLs. Hosts for
     /// non-special URLs are also called opaque hosts.
+    ///
+    /// Opaque hosts are not necessarily DNS domains,
+    /// use [`Url::domain`](crate::Url::domain) and [`Url::opaque_host`](crate::Url::opaque_host)
+    /// to tell them apart.
     Domain(S),
 
     /// An IPv4 address.
@@ -360,6 +379,71 @@ fn parse_ipv4addr(input: &str) -> ParseResult<Ipv4Addr> {
     Ok(Ipv4Addr::from(ipv4))
 }
 
+/// Split the text between the brackets of an IPv6 address literal
+/// into the address and its [RFC 6874](https://tools.ietf.org/html/rfc6874) zone identifier,
+/// without the `%25` delimiter.
+pub(crate) fn split_ipv6_zone(input: &str) -> (&str, Option<&str>) {
+    match input.find("%25") {
+        Some(i) => (&input[..i], Some(&input[i + 3..])),
+        None => (input, None),
+    }
+}
+
+/// Parse the text between the brackets of an IPv6 address literal
+/// that may have a zone identifier.
+///
+/// <https://tools.ietf.org/html/rfc6874#section-2>
+pub(crate) fn parse_ipv6addr_with_zone(input: &str) -> ParseResult<(Ipv6Addr, Option<&str>)> {
+    let (address, zone) = split_ipv6_zone(input);
+    let address = parse_ipv6addr(address)?;
+    if let Some(zone) = zone {
+        // ZoneID = 1*( unreserved / pct-encoded )
+        let bytes = zone.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => i += 1,
+                b'%' if bytes.len() > i + 2
+                    && bytes[i + 1].is_ascii_hexdigit()
+                    && bytes[i + 2].is_ascii_hexdigit() =>
+                {
+                    i += 3
+                }
+                _ => return Err(ParseError::InvalidIpv6Address),
+            }
+        }
+        if zone.is_empty() {
+            return Err(ParseError::InvalidIpv6Address);
+        }
+    }
+    Ok((address, zone))
+}
+
+/// Return whether the text between the brackets of an IP literal is an IPvFuture address.
+///
+/// <https://tools.ietf.org/html/rfc3986#section-3.2.2>
+pub(crate) fn is_ip_future(input: &str) -> bool {
+    // IPvFuture = "v" 1*HEXDIG "." 1*( unreserved / sub-delims / ":" )
+    let rest = match input.strip_prefix(['v', 'V']) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let (version, address) = match rest.split_once('.') {
+        Some(split) => split,
+        None => return false,
+    };
+    !version.is_empty()
+        && version.bytes().all(|b| b.is_ascii_hexdigit())
+        && !address.is_empty()
+        && address.bytes().all(|b| {
+            matches!(b,
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~'
+                | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+                | b':'
+            )
+        })
+}
+
 /// <https://url.spec.whatwg.org/#concept-ipv6-parser>
 fn parse_ipv6addr(input: &str) -> ParseResult<Ipv6Addr> {
     let input = input.as_bytes();
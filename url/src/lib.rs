@@ -121,6 +121,8 @@ If you enable the `serde` feature, [`Url`](struct.Url.html) will implement
 [`serde::Serialize`](https://docs.rs/serde/1/serde/trait.Serialize.html) and
 [`serde::Deserialize`](https://docs.rs/serde/1/serde/trait.Deserialize.html).
 See [serde documentation](https://serde.rs) for more information.
+It also enables [`Url::query_as`] and [`Url::set_query_from`],
+which convert between query strings and types that implement these traits.
 
 ```toml
 url = { version = "2", features = ["serde"] }
@@ -140,9 +142,22 @@ url = { version = "2", features = ["debugger_visualizer"] }
 ```
 
 */
-
+#![cfg_attr(feature = "serde", doc = "[`Url::query_as`]: Url::query_as")]
+#![cfg_attr(
+    feature = "serde",
+    doc = "[`Url::set_query_from`]: Url::set_query_from"
+)]
+#![cfg_attr(
+    not(feature = "serde"),
+    doc = "[`Url::query_as`]: https://docs.rs/url/2/url/struct.Url.html#method.query_as"
+)]
+#![cfg_attr(
+    not(feature = "serde"),
+    doc = "[`Url::set_query_from`]: \
+           https://docs.rs/url/2/url/struct.Url.html#method.set_query_from"
+)]
 #![no_std]
-#![doc(html_root_url = "https://docs.rs/url/2.5.7")]
+#![doc(html_root_url = "https://docs.rs/url/2.6.0")]
 #![cfg_attr(
     feature = "debugger_visualizer",
     debugger_visualizer(natvis_file = "../../debug_metadata/url.natvis")
@@ -164,15 +179,8 @@ use crate::host::HostInternal;
 
 use crate::net::IpAddr;
 #[cfg(feature = "std")]
-#[cfg(any(
-    unix,
-    windows,
-    target_os = "redox",
-    target_os = "wasi",
-    target_os = "hermit"
-))]
-use crate::net::{SocketAddr, ToSocketAddrs};
-use crate::parser::{to_u32, Context, Parser, SchemeType, USERINFO};
+use crate::net::SocketAddr;
+use crate::parser::{to_u32, Context, HostOptions, Parser, SchemeType, USERINFO};
 use alloc::borrow::Cow;
 use alloc::borrow::ToOwned;
 use alloc::str;
@@ -184,13 +192,6 @@ use core::ops::{Range, RangeFrom, RangeTo};
 use core::{cmp, fmt, hash, mem};
 use percent_encoding::utf8_percent_encode;
 #[cfg(feature = "std")]
-#[cfg(any(
-    unix,
-    windows,
-    target_os = "redox",
-    target_os = "wasi",
-    target_os = "hermit"
-))]
 use std::io;
 #[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
@@ -206,18 +207,47 @@ pub(crate) mod net {
     pub use core::net::*;
 }
 
+pub use crate::compact::CompactUrl;
+pub use crate::diff::{Change, PathSegmentChange, QueryPairChange, UrlDiff};
 pub use crate::host::Host;
 pub use crate::origin::{OpaqueOrigin, Origin};
-pub use crate::parser::{ParseError, SyntaxViolation};
-pub use crate::path_segments::PathSegmentsMut;
+pub use crate::parser::{ParseError, SetterError, SyntaxViolation};
+pub use crate::path_segments::{
+    remove_dot_segments, PathSegmentsDecoded, PathSegmentsDecodedLossy, PathSegmentsMut,
+};
+pub use crate::relative::RelativeUrl;
+#[cfg(feature = "std")]
+#[cfg(any(
+    unix,
+    windows,
+    target_os = "redox",
+    target_os = "wasi",
+    target_os = "hermit"
+))]
+pub use crate::resolve::SystemResolver;
+#[cfg(feature = "tokio")]
+pub use crate::resolve::TokioResolver;
+#[cfg(feature = "std")]
+pub use crate::resolve::{AsyncResolver, MemoryResolver, Resolver};
+pub use crate::shared::ArcUrl;
 pub use crate::slicing::Position;
+pub use crate::static_url::StaticUrl;
 pub use form_urlencoded::EncodingOverride;
 
+mod compact;
+mod diff;
 mod host;
 mod origin;
 mod parser;
 mod path_segments;
+mod relative;
+#[cfg(feature = "std")]
+mod resolve;
+mod shared;
 mod slicing;
+mod static_url;
+#[cfg(feature = "std")]
+mod wtf8;
 
 #[doc(hidden)]
 pub mod quirks;
@@ -255,6 +285,7 @@ pub struct ParseOptions<'a> {
     base_url: Option<&'a Url>,
     encoding_override: EncodingOverride<'a>,
     violation_fn: Option<&'a dyn Fn(SyntaxViolation)>,
+    host_options: HostOptions,
 }
 
 impl<'a> ParseOptions<'a> {
@@ -301,6 +332,67 @@ impl<'a> ParseOptions<'a> {
         self
     }
 
+    /// Accept [RFC 6874](https://tools.ietf.org/html/rfc6874) zone identifiers
+    /// after IPv6 addresses, as in `http://[fe80::1%25eth0]/`.
+    ///
+    /// Zone identifiers are not part of the URL Standard and are rejected by default.
+    /// They are only meaningful on the host where the URL was created,
+    /// and are kept in the serialization and returned by [`Url::ipv6_zone_id`].
+    /// [`Url::host`] returns the address without its zone identifier.
+    ///
+    /// The option is not stored in the resulting [`Url`]:
+    /// parsing its serialization again requires this option again,
+    /// and [`Url::set_host`] rejects zone identifiers.
+    /// [`Url::join`] accepts them if the base URL has one,
+    /// and deserializing with the `serde` feature always accepts them.
+    ///
+    /// ## Example
+    /// ```
+    /// use url::{Host, Url};
+    /// # fn run() -> Result<(), url::ParseError> {
+    /// let input = "http://[fe80::1%25eth0]:8080/";
+    /// assert!(Url::parse(input).is_err());
+    ///
+    /// let url = Url::options().ipv6_zone_ids(true).parse(input)?;
+    /// assert_eq!(url.host_str(), Some("[fe80::1%25eth0]"));
+    /// assert_eq!(url.host(), Some(Host::Ipv6("fe80::1".parse().unwrap())));
+    /// assert_eq!(url.ipv6_zone_id(), Some("eth0"));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn ipv6_zone_ids(mut self, allow: bool) -> Self {
+        self.host_options.ipv6_zone_ids = allow;
+        self
+    }
+
+    /// Accept [RFC 3986](https://tools.ietf.org/html/rfc3986#section-3.2.2) IPvFuture
+    /// literals as the host of non-special URLs, as in `myproto://[v7.node-3]/`.
+    ///
+    /// IPvFuture literals are not part of the URL Standard and are rejected by default.
+    /// They are kept as written in the serialization,
+    /// and [`Url::host`] returns them as an opaque `Host::Domain` including the brackets.
+    ///
+    /// ## Example
+    /// ```
+    /// use url::{Host, Url};
+    /// # fn run() -> Result<(), url::ParseError> {
+    /// let input = "myproto://[v7.node-3]:9000/status";
+    /// assert!(Url::parse(input).is_err());
+    ///
+    /// let url = Url::options().ip_future_literals(true).parse(input)?;
+    /// assert_eq!(url.host(), Some(Host::Domain("[v7.node-3]")));
+    /// assert_eq!(url.port(), Some(9000));
+    /// assert_eq!(url.as_str(), input);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn ip_future_literals(mut self, allow: bool) -> Self {
+        self.host_options.ip_future = allow;
+        self
+    }
+
     /// Parse an URL string with the configuration so far.
     pub fn parse(self, input: &str) -> Result<Url, crate::ParseError> {
         Parser {
@@ -309,6 +401,7 @@ impl<'a> ParseOptions<'a> {
             query_encoding_override: self.encoding_override,
             violation_fn: self.violation_fn,
             context: Context::UrlParser,
+            host_options: self.host_options,
         }
         .parse_url(input)
     }
@@ -464,11 +557,52 @@ impl Url {
     /// If the function can not parse an URL from the given string
     /// with this URL as the base URL, a [`ParseError`] variant will be returned.
     ///
+    /// If this URL has an [IPv6 zone identifier](Url::ipv6_zone_id),
+    /// zone identifiers are also accepted in `input`.
+    ///
     /// [`ParseError`]: enum.ParseError.html
     /// [`make_relative`]: #method.make_relative
     #[inline]
     pub fn join(&self, input: &str) -> Result<Self, crate::ParseError> {
-        Self::options().base_url(Some(self)).parse(input)
+        Self::options()
+            .base_url(Some(self))
+            .ipv6_zone_ids(self.ipv6_zone_id().is_some())
+            .parse(input)
+    }
+
+    /// Parse a URL reference that was parsed without a base, with this URL as the base URL.
+    ///
+    /// This returns the same result as `self.join(reference_input)`,
+    /// where `reference_input` is the string `reference` was parsed from.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{RelativeUrl, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let reference = RelativeUrl::parse("../api/v1?q=1")?;
+    ///
+    /// let base = Url::parse("https://example.net/a/b")?;
+    /// let url = base.join_relative(&reference)?;
+    /// assert_eq!(url.as_str(), "https://example.net/api/v1?q=1");
+    ///
+    /// let base = Url::parse("https://example.org/x/y/z")?;
+    /// let url = base.join_relative(&reference)?;
+    /// assert_eq!(url.as_str(), "https://example.org/x/api/v1?q=1");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the function can not parse an URL from the reference
+    /// with this URL as the base URL, a [`ParseError`] variant will be returned.
+    #[inline]
+    pub fn join_relative(&self, reference: &RelativeUrl) -> Result<Self, crate::ParseError> {
+        self.join(reference.as_str())
     }
 
     /// Creates a relative URL if possible, with this URL as the base URL.
@@ -603,6 +737,114 @@ impl Url {
         Some(relative)
     }
 
+    /// Creates the shortest reference string that, when joined with this URL, gives `url`.
+    ///
+    /// Unlike [`make_relative`](Url::make_relative), this never fails:
+    /// depending on what `url` has in common with this URL, the result is a fragment,
+    /// a query, a relative path, an absolute path (`/path`),
+    /// a scheme-relative reference (`//host/path`) or the serialization of `url` itself.
+    ///
+    /// `self.join(&self.make_relative_any(url))` returns `url` whenever `url`
+    /// can be parsed back from its own serialization,
+    /// which is the case for all URLs created with [`Url::parse`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let base = Url::parse("https://example.net/a/b.html")?;
+    ///
+    /// let url = Url::parse("https://example.net/a/c.png")?;
+    /// assert_eq!(base.make_relative_any(&url), "c.png");
+    ///
+    /// let url = Url::parse("https://cdn.example.net/a/c.png")?;
+    /// assert_eq!(base.make_relative_any(&url), "//cdn.example.net/a/c.png");
+    ///
+    /// let url = Url::parse("http://example.net/a/c.png")?;
+    /// assert_eq!(base.make_relative_any(&url), "http://example.net/a/c.png");
+    ///
+    /// let url = Url::parse("https://example.net/a/b.html#top")?;
+    /// assert_eq!(base.make_relative_any(&url), "#top");
+    ///
+    /// for url in ["https://example.net/x:y", "https://user@example.net/"] {
+    ///     let url = Url::parse(url)?;
+    ///     assert_eq!(base.join(&base.make_relative_any(&url))?, url);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn make_relative_any(&self, url: &Self) -> String {
+        let mut candidates: alloc::vec::Vec<Cow<'_, str>> = alloc::vec::Vec::new();
+        if url[..Position::AfterQuery] == self[..Position::AfterQuery] {
+            match url.fragment_start {
+                Some(start) => candidates.push(url.slice(start..).into()),
+                None => candidates.push("".into()),
+            }
+        }
+        if url[..Position::AfterPath] == self[..Position::AfterPath] && url.query().is_some() {
+            candidates.push(url[Position::AfterPath..].into());
+        }
+        if let Some(relative) = self.make_relative(url) {
+            // A first segment with a colon would be parsed as a scheme.
+            candidates.push(format!("./{relative}").into());
+            candidates.push(relative.into());
+        }
+        if url[..Position::BeforePath] == self[..Position::BeforePath] {
+            candidates.push(url[Position::BeforePath..].into());
+        }
+        if url.scheme() == self.scheme() && url.has_authority() {
+            candidates.push(url.as_str()[url.scheme().len() + 1..].into());
+        }
+        // `sort_by_key` is stable, so shorter kinds of references come first on equal length.
+        candidates.sort_by_key(|candidate| candidate.len());
+        candidates
+            .into_iter()
+            .find(|candidate| self.join(candidate).as_ref() == Ok(url))
+            .map_or_else(|| url.as_str().to_owned(), Cow::into_owned)
+    }
+
+    /// Return the component-wise differences between this URL and `other`,
+    /// for example between two steps of a redirect chain.
+    ///
+    /// The returned [`UrlDiff`] can be replayed on a URL with [`UrlDiff::apply`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{Change, PathSegmentChange, QueryPairChange, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let old = Url::parse("http://example.net/a/b?page=1&lang=en")?;
+    /// let new = Url::parse("https://example.net/a/c/d?page=2&lang=en")?;
+    /// let diff = old.diff(&new);
+    ///
+    /// assert_eq!(diff.scheme, Some(Change { old: "http".into(), new: "https".into() }));
+    /// assert_eq!(diff.host, None);
+    /// assert_eq!(
+    ///     diff.path_segments,
+    ///     [
+    ///         PathSegmentChange::Changed { index: 1, old: "b".into(), new: "c".into() },
+    ///         PathSegmentChange::Added { index: 2, segment: "d".into() },
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     diff.query_pairs,
+    ///     [QueryPairChange::Changed { name: "page".into(), index: 0, old: "1".into(), new: "2".into() }]
+    /// );
+    /// assert!(old.diff(&old).is_empty());
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn diff(&self, other: &Url) -> UrlDiff {
+        UrlDiff::new(self, other)
+    }
+
     /// Return a default `ParseOptions` that can fully configure the URL parser.
     ///
     /// # Examples
@@ -627,6 +869,7 @@ impl Url {
             base_url: None,
             encoding_override: None,
             violation_fn: None,
+            host_options: HostOptions::default(),
         }
     }
 
@@ -738,12 +981,15 @@ impl Url {
                 HostInternal::Ipv4(address) => assert_eq!(host_str, address.to_string()),
                 HostInternal::Ipv6(address) => {
                     let h: Host<String> = Host::Ipv6(address);
-                    assert_eq!(host_str, h.to_string())
+                    let (literal, _zone) = host::split_ipv6_zone(&host_str[1..host_str.len() - 1]);
+                    assert_eq!(format!("[{literal}]"), h.to_string())
                 }
                 HostInternal::Domain => {
-                    if SchemeType::from(self.scheme()).is_special() {
-                        assert!(!host_str.is_empty())
-                    }
+                    assert!(SchemeType::from(self.scheme()).is_special());
+                    assert!(!host_str.is_empty())
+                }
+                HostInternal::Opaque => {
+                    assert!(!SchemeType::from(self.scheme()).is_special());
                 }
             }
             if self.path_start == self.host_end {
@@ -788,7 +1034,11 @@ impl Url {
             assert!(fragment_start > query_start);
         }
 
-        let other = Self::parse(self.as_str()).expect("Failed to parse myself?");
+        let other = Self::options()
+            .ipv6_zone_ids(self.ipv6_zone_id().is_some())
+            .ip_future_literals(self.has_ip_future_host())
+            .parse(self.as_str())
+            .expect("Failed to parse myself?");
         assert_eq!(&self.serialization, &other.serialization);
         assert_eq!(self.scheme_end, other.scheme_end);
         assert_eq!(self.username_end, other.username_end);
@@ -1173,6 +1423,9 @@ impl Url {
     /// Cannot-be-a-base URLs (typical of `data:` and `mailto:`) and some `file:` URLs
     /// don’t have a host.
     ///
+    /// The opaque host of a non-special URL is returned as `Host::Domain`,
+    /// see [`Url::opaque_host`] to tell it apart from a DNS domain.
+    ///
     /// See also the `host_str` method.
     ///
     /// # Examples
@@ -1200,15 +1453,19 @@ impl Url {
     pub fn host(&self) -> Option<Host<&str>> {
         match self.host {
             HostInternal::None => None,
-            HostInternal::Domain => Some(Host::Domain(self.slice(self.host_start..self.host_end))),
+            HostInternal::Domain | HostInternal::Opaque => {
+                Some(Host::Domain(self.slice(self.host_start..self.host_end)))
+            }
             HostInternal::Ipv4(address) => Some(Host::Ipv4(address)),
             HostInternal::Ipv6(address) => Some(Host::Ipv6(address)),
         }
     }
 
     /// If this URL has a host and it is a domain name (not an IP address), return it.
-    /// Non-ASCII domains are punycode-encoded per IDNA if this is the host
-    /// of a special URL, or percent encoded for non-special URLs.
+    /// Non-ASCII domains are punycode-encoded per IDNA.
+    ///
+    /// Only special URLs have domains.
+    /// The host of a non-special URL is an opaque host, returned by [`Url::opaque_host`].
     ///
     /// # Examples
     ///
@@ -1229,6 +1486,9 @@ impl Url {
     /// let url = Url::parse("https://subdomain.example.com/")?;
     /// assert_eq!(url.domain(), Some("subdomain.example.com"));
     ///
+    /// let url = Url::parse("foo://example.com/")?;
+    /// assert_eq!(url.domain(), None);
+    ///
     /// # Ok(())
     /// # }
     /// # run().unwrap();
@@ -1240,6 +1500,74 @@ impl Url {
         }
     }
 
+    /// If this URL is not special and has a host that is not an IP address, return it.
+    ///
+    /// Such hosts are called opaque hosts: they are not necessarily DNS domains,
+    /// and non-ASCII characters are percent-encoded rather than punycode-encoded.
+    /// For compatibility, [`Url::host`] returns them as `Host::Domain`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::{Host, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let url = Url::parse("foo://💩/")?;
+    /// assert_eq!(url.opaque_host(), Some("%F0%9F%92%A9"));
+    /// assert_eq!(url.domain(), None);
+    /// assert_eq!(url.host(), Some(Host::Domain("%F0%9F%92%A9")));
+    ///
+    /// let url = Url::parse("https://example.com/")?;
+    /// assert_eq!(url.opaque_host(), None);
+    ///
+    /// let url = Url::parse("foo://[::1]/")?;
+    /// assert_eq!(url.opaque_host(), None);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn opaque_host(&self) -> Option<&str> {
+        match self.host {
+            HostInternal::Opaque => Some(self.slice(self.host_start..self.host_end)),
+            _ => None,
+        }
+    }
+
+    /// If this URL’s host is an IPv6 address with a zone identifier, return that identifier.
+    ///
+    /// Zone identifiers are only accepted when parsing with
+    /// [`ParseOptions::ipv6_zone_ids`],
+    /// which is also needed to parse the serialization of this URL again.
+    /// The identifier is returned as it appears in the serialization,
+    /// after the percent-encoded `%25` delimiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let url = Url::options().ipv6_zone_ids(true).parse("http://[fe80::1%25en0]/")?;
+    /// assert_eq!(url.ipv6_zone_id(), Some("en0"));
+    ///
+    /// let url = Url::parse("http://[fe80::1]/")?;
+    /// assert_eq!(url.ipv6_zone_id(), None);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn ipv6_zone_id(&self) -> Option<&str> {
+        match self.host {
+            HostInternal::Ipv6(_) => {
+                let literal = self.slice(self.host_start + 1..self.host_end - 1);
+                host::split_ipv6_zone(literal).1
+            }
+            _ => None,
+        }
+    }
+
     /// Return the port number for this URL, if any.
     ///
     /// Note that default port numbers are never reflected by the serialization,
@@ -1309,6 +1637,9 @@ impl Url {
     /// or by matching on the URL’s `.scheme()`.
     ///
     /// If the host is a domain, it is resolved using the standard library’s DNS support.
+    /// If the host is an IPv6 address with a [zone identifier](Url::ipv6_zone_id),
+    /// the zone is converted to the `scope_id` of the `SocketAddrV6`:
+    /// numeric zones are used as is and, on Unix, interface names are looked up.
     ///
     /// # Examples
     ///
@@ -1348,20 +1679,105 @@ impl Url {
         // > This RFC proposes that *all* type parameters are considered in scope
         // > for `impl Trait` in return position
 
+        let (host, port) = self.resolution_target(default_port_number)?;
+        Ok(match host {
+            Host::Domain(domain) => SystemResolver.resolve(domain, port)?,
+            Host::Ipv4(ip) => vec![(ip, port).into()],
+            Host::Ipv6(ip) => vec![self.ipv6_socket_addr(ip, port)?],
+        })
+    }
+
+    /// Resolve a URL’s host and port number to `SocketAddr` with a custom resolver.
+    ///
+    /// Like [`Url::socket_addrs`], the port number is the URL’s
+    /// [`port_or_known_default`](Url::port_or_known_default),
+    /// and `default_port_number` provides one for schemes without a known default.
+    /// If the host is an IPv4 or IPv6 address, the resolver is not used.
+    /// Otherwise the resolver is given the domain, or the [opaque host](Url::opaque_host)
+    /// of a non-special URL as it is serialized.
+    ///
+    /// See [`MemoryResolver`] for an example.
+    #[cfg(feature = "std")]
+    pub fn resolve_with<R>(
+        &self,
+        resolver: &R,
+        default_port_number: impl Fn() -> Option<u16>,
+    ) -> io::Result<alloc::vec::Vec<SocketAddr>>
+    where
+        R: Resolver + ?Sized,
+    {
+        let (host, port) = self.resolution_target(default_port_number)?;
+        Ok(match host {
+            Host::Domain(domain) => resolver.resolve(domain, port)?,
+            Host::Ipv4(ip) => vec![(ip, port).into()],
+            Host::Ipv6(ip) => vec![self.ipv6_socket_addr(ip, port)?],
+        })
+    }
+
+    /// Resolve a URL’s host and port number to `SocketAddr` with an asynchronous resolver.
+    ///
+    /// This is the asynchronous version of [`Url::resolve_with`].
+    /// With the `tokio` Cargo feature, [`TokioResolver`] uses Tokio’s resolver:
+    ///
+    /// ```ignore
+    /// let addrs = url.resolve_with_async(&url::TokioResolver, || None).await?;
+    /// let stream = tokio::net::TcpStream::connect(&*addrs).await?;
+    /// ```
+    #[cfg_attr(feature = "tokio", doc = "[`TokioResolver`]: crate::TokioResolver")]
+    #[cfg_attr(
+        not(feature = "tokio"),
+        doc = "[`TokioResolver`]: https://docs.rs/url/2/url/struct.TokioResolver.html"
+    )]
+    #[cfg(feature = "std")]
+    pub async fn resolve_with_async<R>(
+        &self,
+        resolver: &R,
+        default_port_number: impl Fn() -> Option<u16>,
+    ) -> io::Result<alloc::vec::Vec<SocketAddr>>
+    where
+        R: AsyncResolver + ?Sized,
+    {
+        let (host, port) = self.resolution_target(default_port_number)?;
+        Ok(match host {
+            Host::Domain(domain) => resolver.resolve(domain, port).await?,
+            Host::Ipv4(ip) => vec![(ip, port).into()],
+            Host::Ipv6(ip) => vec![self.ipv6_socket_addr(ip, port)?],
+        })
+    }
+
+    /// Return whether the host is an IPvFuture literal,
+    /// the only kind of opaque host that starts with a bracket.
+    fn has_ip_future_host(&self) -> bool {
+        self.host == HostInternal::Opaque && self.byte_at(self.host_start) == b'['
+    }
+
+    #[cfg(feature = "std")]
+    fn ipv6_socket_addr(&self, ip: net::Ipv6Addr, port: u16) -> io::Result<SocketAddr> {
+        let scope_id = match self.ipv6_zone_id() {
+            Some(zone) => resolve::ipv6_scope_id(zone)?,
+            None => 0,
+        };
+        Ok(net::SocketAddrV6::new(ip, port, 0, scope_id).into())
+    }
+
+    #[cfg(feature = "std")]
+    fn resolution_target(
+        &self,
+        default_port_number: impl FnOnce() -> Option<u16>,
+    ) -> io::Result<(Host<&str>, u16)> {
         fn io_result<T>(opt: Option<T>, message: &str) -> io::Result<T> {
             opt.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, message))
         }
 
         let host = io_result(self.host(), "No host name in the URL")?;
+        if self.has_ip_future_host() {
+            return io_result(None, "IPvFuture addresses can not be resolved");
+        }
         let port = io_result(
             self.port_or_known_default().or_else(default_port_number),
             "No port number in the URL",
         )?;
-        Ok(match host {
-            Host::Domain(domain) => (domain, port).to_socket_addrs()?.collect(),
-            Host::Ipv4(ip) => vec![(ip, port).into()],
-            Host::Ipv6(ip) => vec![(ip, port).into()],
-        })
+        Ok((host, port))
     }
 
     /// Return the path for this URL, as a percent-encoded ASCII string.
@@ -1443,6 +1859,104 @@ impl Url {
         path.strip_prefix('/').map(|remainder| remainder.split('/'))
     }
 
+    /// Unless this URL is cannot-be-a-base,
+    /// return an iterator of its percent-decoded path segments.
+    ///
+    /// This is like [`Url::path_segments`], but each segment is percent-decoded
+    /// and returned as `Err` if the result is not valid UTF-8.
+    /// Segments without percent-encoded bytes are borrowed.
+    /// The iterator can also be used from the end and knows its length.
+    ///
+    /// See [`Url::path_segments_decoded_lossy`] to replace invalid UTF-8 instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let url = Url::parse("https://example.com/countries/việt nam/%FF")?;
+    /// let mut path_segments = url.path_segments_decoded().ok_or("cannot be base")?;
+    /// assert_eq!(path_segments.len(), 3);
+    /// assert!(path_segments.next_back().unwrap().is_err());
+    /// assert_eq!(path_segments.next().unwrap()?, "countries");
+    /// assert_eq!(path_segments.next().unwrap()?, "việt nam");
+    /// assert!(path_segments.next().is_none());
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn path_segments_decoded(&self) -> Option<PathSegmentsDecoded<'_>> {
+        let path = self.path();
+        path.strip_prefix('/').map(path_segments::decoded)
+    }
+
+    /// Unless this URL is cannot-be-a-base,
+    /// return an iterator of its percent-decoded path segments,
+    /// with invalid UTF-8 replaced by U+FFFD REPLACEMENT CHARACTER.
+    ///
+    /// This is like [`Url::path_segments_decoded`], but never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let url = Url::parse("https://example.com/users/j%C3%BCrgen/%FFfiles")?;
+    /// let segments: Vec<_> = url.path_segments_decoded_lossy().ok_or("cannot be base")?.collect();
+    /// match segments.iter().map(|segment| &**segment).collect::<Vec<_>>()[..] {
+    ///     ["users", name, rest] => {
+    ///         assert_eq!(name, "jürgen");
+    ///         assert_eq!(rest, "\u{FFFD}files");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn path_segments_decoded_lossy(&self) -> Option<PathSegmentsDecodedLossy<'_>> {
+        let path = self.path();
+        path.strip_prefix('/').map(path_segments::decoded_lossy)
+    }
+
+    /// Return the path segment at position `index`, as a percent-encoded ASCII string.
+    ///
+    /// Return `None` if this URL is cannot-be-a-base
+    /// or if its path has `index` segments or fewer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let url = Url::parse("https://example.com/api/v2/users/42")?;
+    /// assert_eq!(url.path_segment(1), Some("v2"));
+    /// assert_eq!(url.path_segment(4), None);
+    ///
+    /// let url = Url::parse("data:text/plain,HelloWorld")?;
+    /// assert_eq!(url.path_segment(0), None);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn path_segment(&self, index: usize) -> Option<&str> {
+        self.path_segments()?.nth(index)
+    }
+
     /// Return this URL’s query string, if any, as a percent-encoded ASCII string.
     ///
     /// # Examples
@@ -1509,6 +2023,81 @@ impl Url {
         form_urlencoded::parse(self.query().unwrap_or("").as_bytes())
     }
 
+    /// Like [`query_pairs`](Self::query_pairs), but decode percent-decoded names and values
+    /// with the given function instead of as UTF-8.
+    ///
+    /// This is a legacy concept only relevant for HTML, where queries use the character
+    /// encoding of their document: see also [`ParseOptions::encoding_override`].
+    /// With its `encoding_rs` feature, `form_urlencoded::decode_legacy` provides decoders
+    /// for the encodings of the Encoding Standard.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    ///
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// fn latin1(bytes: &[u8]) -> Cow<'_, str> {
+    ///     bytes.iter().map(|&b| char::from(b)).collect()
+    /// }
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let url = Url::parse("https://example.com/search?q=caf%E9")?;
+    /// let mut pairs = url.query_pairs_with_encoding(&latin1);
+    ///
+    /// assert_eq!(pairs.next(), Some((Cow::Borrowed("q"), Cow::Borrowed("café"))));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    #[inline]
+    pub fn query_pairs_with_encoding<'a>(
+        &'a self,
+        decoder: &'a dyn Fn(&[u8]) -> Cow<'_, str>,
+    ) -> form_urlencoded::Parse<'a> {
+        form_urlencoded::parse_with_decoder(self.query().unwrap_or("").as_bytes(), decoder)
+    }
+
+    /// Deserialize the URL’s query string, if any, as `application/x-www-form-urlencoded`.
+    ///
+    /// The value can be a struct, a map or a sequence of (name, value) pairs:
+    /// see `form_urlencoded::from_str` for how it is deserialized.
+    /// A URL without a query string is deserialized like an empty query string.
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// #[derive(serde_derive::Deserialize, Debug, PartialEq)]
+    /// struct Search {
+    ///     q: String,
+    ///     page: Option<u32>,
+    ///     tag: Vec<String>,
+    /// }
+    ///
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let url = Url::parse("https://example.com/search?q=rust+%26+url&tag=a&tag=b")?;
+    /// let search: Search = url.query_as()?;
+    /// assert_eq!(search.q, "rust & url");
+    /// assert_eq!(search.page, None);
+    /// assert_eq!(search.tag, ["a", "b"]);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn query_as<'a, T>(&'a self) -> Result<T, form_urlencoded::de::Error>
+    where
+        T: serde::Deserialize<'a>,
+    {
+        form_urlencoded::from_str(self.query().unwrap_or(""))
+    }
+
     /// Return this URL’s fragment identifier, if any.
     ///
     /// A fragment is the part of the URL after the `#` symbol.
@@ -1717,6 +2306,45 @@ impl Url {
         form_urlencoded::Serializer::for_suffix(query, query_start + "?".len())
     }
 
+    /// Replace this URL’s query string with the given value
+    /// serialized as `application/x-www-form-urlencoded`.
+    ///
+    /// The value can be a struct, a map or a sequence of (name, value) pairs:
+    /// see `form_urlencoded::to_string` for how it is serialized.
+    /// If it serializes to an empty string, the query string is removed.
+    /// If an error occurs, this URL is unchanged.
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// #[derive(serde_derive::Serialize)]
+    /// struct Search<'a> {
+    ///     q: &'a str,
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut url = Url::parse("https://example.com/search?old=1#results")?;
+    /// url.set_query_from(&Search { q: "rust & url", page: Some(2) })?;
+    /// assert_eq!(url.as_str(), "https://example.com/search?q=rust+%26+url&page=2#results");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn set_query_from<T>(&mut self, value: &T) -> Result<(), form_urlencoded::ser::Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let query = form_urlencoded::to_string(value)?;
+        self.set_query(if query.is_empty() { None } else { Some(&query) });
+        Ok(())
+    }
+
     fn take_after_path(&mut self) -> String {
         match (self.query_start, self.fragment_start) {
             (Some(i), _) | (None, Some(i)) => {
@@ -1785,16 +2413,123 @@ impl Url {
                 );
             }
         });
+        self.update_path_prefix();
         self.restore_after_path(old_after_path_pos, &after_path);
     }
 
+    /// Like the parser, add a "/." prefix before a path starting with an empty segment
+    /// so that it is not serialized as an authority, or remove it if it is no longer needed.
+    ///
+    /// This must be called after changing the path of a URL
+    /// whose query and fragment were removed with `take_after_path`.
+    pub(crate) fn update_path_prefix(&mut self) {
+        // Not `has_authority()`: a path starting with "//" looks like an authority.
+        if self.host_start != self.scheme_end + 1 {
+            return;
+        }
+        let prefix_start = self.scheme_end as usize + 1;
+        let has_prefix = self.slice(self.scheme_end + 1..self.path_start) == "/.";
+        let needs_prefix = self.slice(self.path_start..).starts_with("//");
+        if has_prefix && !needs_prefix {
+            self.serialization
+                .replace_range(prefix_start..prefix_start + 2, "");
+            self.path_start -= 2;
+        } else if needs_prefix && !has_prefix {
+            self.serialization.insert_str(prefix_start, "/.");
+            self.path_start += 2;
+        }
+    }
+
+    /// Normalize this URL’s path as described in
+    /// [RFC 3986 section 6.2.2](https://www.rfc-editor.org/rfc/rfc3986#section-6.2.2).
+    ///
+    /// Percent-encoded unreserved characters (ASCII alphanumerics, `-`, `.`, `_` and `~`)
+    /// are decoded, the hexadecimal digits of other percent-encoded bytes are uppercased,
+    /// and dot segments are removed with [`remove_dot_segments`].
+    ///
+    /// Does nothing if this URL is cannot-be-a-base.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::Url;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("https://example.net/%7euser/a%2fb/%41%2e%2E?q=%7e")?;
+    /// url.normalize_path();
+    /// assert_eq!(url.as_str(), "https://example.net/~user/a%2Fb/A..?q=%7e");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn normalize_path(&mut self) {
+        if self.cannot_be_a_base() {
+            return;
+        }
+        let path = self.path();
+        let mut normalized = String::with_capacity(path.len());
+        let mut rest = path;
+        while let Some(percent) = rest.find('%') {
+            normalized.push_str(&rest[..percent]);
+            let hex = rest
+                .get(percent + 1..percent + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()));
+            match hex {
+                Some(hex) => {
+                    let byte = u8::from_str_radix(hex, 16).unwrap();
+                    if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                        normalized.push(byte as char);
+                    } else {
+                        normalized.push('%');
+                        normalized.push_str(&hex.to_ascii_uppercase());
+                    }
+                    rest = &rest[percent + 3..];
+                }
+                None => {
+                    normalized.push('%');
+                    rest = &rest[percent + 1..];
+                }
+            }
+        }
+        normalized.push_str(rest);
+        let normalized = remove_dot_segments(&normalized).into_owned();
+        if normalized != self.path() {
+            self.set_path(&normalized);
+        }
+    }
+
     /// Return an object with methods to manipulate this URL’s path segments.
     ///
     /// Return `Err(())` if this URL is cannot-be-a-base.
+    ///
+    /// See [`Url::try_path_segments_mut`] for a variant with a typed error.
     #[allow(clippy::result_unit_err)]
     pub fn path_segments_mut(&mut self) -> Result<PathSegmentsMut<'_>, ()> {
+        self.try_path_segments_mut().map_err(|_| ())
+    }
+
+    /// Return an object with methods to manipulate this URL’s path segments.
+    ///
+    /// Like [`Url::path_segments_mut`],
+    /// but return [`SetterError::CannotBeABase`] if this URL is cannot-be-a-base.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{SetterError, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("mailto:rms@example.net")?;
+    /// assert_eq!(url.try_path_segments_mut().err(), Some(SetterError::CannotBeABase));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn try_path_segments_mut(&mut self) -> Result<PathSegmentsMut<'_>, SetterError> {
         if self.cannot_be_a_base() {
-            Err(())
+            Err(SetterError::CannotBeABase)
         } else {
             Ok(path_segments::new(self))
         }
@@ -1883,12 +2618,39 @@ impl Url {
     /// # }
     /// # run().unwrap();
     /// ```
+    ///
+    /// See [`Url::try_set_port`] for a variant with a typed error.
     #[allow(clippy::result_unit_err)]
-    pub fn set_port(&mut self, mut port: Option<u16>) -> Result<(), ()> {
-        // has_host implies !cannot_be_a_base
-        if !self.has_host() || self.host() == Some(Host::Domain("")) || self.scheme() == "file" {
-            return Err(());
-        }
+    pub fn set_port(&mut self, port: Option<u16>) -> Result<(), ()> {
+        self.try_set_port(port).map_err(|_| ())
+    }
+
+    /// Change this URL’s port number.
+    ///
+    /// Like [`Url::set_port`], but return a [`SetterError`] describing
+    /// why the port can not be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{SetterError, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("mailto:rms@example.net")?;
+    /// assert_eq!(url.try_set_port(Some(80)), Err(SetterError::CannotBeABase));
+    ///
+    /// let mut url = Url::parse("file:///tmp/foo")?;
+    /// assert_eq!(url.try_set_port(Some(80)), Err(SetterError::FileWithCredentialsOrPort));
+    ///
+    /// let mut url = Url::parse("unix:/run/foo.socket")?;
+    /// assert_eq!(url.try_set_port(Some(80)), Err(SetterError::NoHost));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn try_set_port(&mut self, mut port: Option<u16>) -> Result<(), SetterError> {
+        self.check_can_have_credentials_or_port()?;
         if port.is_some() && port == parser::default_port(self.scheme()) {
             port = None
         }
@@ -1896,6 +2658,19 @@ impl Url {
         Ok(())
     }
 
+    /// <https://url.spec.whatwg.org/#cannot-have-a-username-password-port>
+    fn check_can_have_credentials_or_port(&self) -> Result<(), SetterError> {
+        if self.cannot_be_a_base() {
+            Err(SetterError::CannotBeABase)
+        } else if self.scheme() == "file" {
+            Err(SetterError::FileWithCredentialsOrPort)
+        } else if !self.has_host() || self.host() == Some(Host::Domain("")) {
+            Err(SetterError::NoHost)
+        } else {
+            Ok(())
+        }
+    }
+
     fn set_port_internal(&mut self, port: Option<u16>) {
         match (self.port, port) {
             (None, None) => {}
@@ -2015,6 +2790,7 @@ impl Url {
     ///
     /// If this URL is cannot-be-a-base or there is an error parsing the given `host`,
     /// a [`ParseError`] variant will be returned.
+    /// IPv6 [zone identifiers](ParseOptions::ipv6_zone_ids) are rejected.
     ///
     /// [`ParseError`]: enum.ParseError.html
     pub fn set_host(&mut self, host: Option<&str>) -> Result<(), ParseError> {
@@ -2051,8 +2827,15 @@ impl Url {
         } else if self.has_host() {
             if scheme_type.is_special() && !scheme_type.is_file() {
                 return Err(ParseError::EmptyHost);
-            } else if self.serialization.len() == self.path_start as usize {
-                self.serialization.push('/');
+            } else if !self.slice(self.path_start..).starts_with('/') {
+                // An empty path becomes `/`, so that it is not mistaken for the authority.
+                self.serialization.insert(self.path_start as usize, '/');
+                if let Some(ref mut index) = self.query_start {
+                    *index += 1
+                }
+                if let Some(ref mut index) = self.fragment_start {
+                    *index += 1
+                }
             }
             debug_assert!(self.byte_at(self.scheme_end) == b':');
             debug_assert!(self.byte_at(self.path_start) == b'/');
@@ -2070,6 +2853,7 @@ impl Url {
             self.username_end = new_path_start;
             self.host_start = new_path_start;
             self.host_end = new_path_start;
+            self.host = HostInternal::None;
             self.port = None;
             if let Some(ref mut index) = self.query_start {
                 *index -= offset
@@ -2083,11 +2867,15 @@ impl Url {
 
     /// opt_new_port: None means leave unchanged, Some(None) means remove any port number.
     fn set_host_internal(&mut self, host: Host<Cow<'_, str>>, opt_new_port: Option<Option<u16>>) {
-        let old_suffix_pos = if opt_new_port.is_some() {
+        let mut old_suffix_pos = if opt_new_port.is_some() {
             self.path_start
         } else {
             self.host_end
         };
+        if !self.has_authority() && self.slice(old_suffix_pos..self.path_start) == "/." {
+            // The "/." prefix of a path starting with "//" is not needed after a host.
+            old_suffix_pos = self.path_start;
+        }
         let suffix = self.slice(old_suffix_pos..).to_owned();
         self.serialization.truncate(self.host_start as usize);
         if !self.has_authority() {
@@ -2100,7 +2888,7 @@ impl Url {
         }
         write!(&mut self.serialization, "{host}").unwrap();
         self.host_end = to_u32(self.serialization.len()).unwrap();
-        self.host = host.into();
+        self.host = HostInternal::from(host).for_scheme(SchemeType::from(self.scheme()));
 
         if let Some(new_port) = opt_new_port {
             self.port = new_port;
@@ -2161,10 +2949,19 @@ impl Url {
     /// # run().unwrap();
     /// ```
     ///
+    /// See [`Url::try_set_ip_host`] for a variant with a typed error.
     #[allow(clippy::result_unit_err)]
     pub fn set_ip_host(&mut self, address: IpAddr) -> Result<(), ()> {
+        self.try_set_ip_host(address).map_err(|_| ())
+    }
+
+    /// Change this URL’s host to the given IP address.
+    ///
+    /// Like [`Url::set_ip_host`],
+    /// but return [`SetterError::CannotBeABase`] if this URL is cannot-be-a-base.
+    pub fn try_set_ip_host(&mut self, address: IpAddr) -> Result<(), SetterError> {
         if self.cannot_be_a_base() {
-            return Err(());
+            return Err(SetterError::CannotBeABase);
         }
 
         let address = match address {
@@ -2201,12 +2998,34 @@ impl Url {
     /// # }
     /// # run().unwrap();
     /// ```
+    ///
+    /// See [`Url::try_set_password`] for a variant with a typed error.
     #[allow(clippy::result_unit_err)]
     pub fn set_password(&mut self, password: Option<&str>) -> Result<(), ()> {
-        // has_host implies !cannot_be_a_base
-        if !self.has_host() || self.host() == Some(Host::Domain("")) || self.scheme() == "file" {
-            return Err(());
-        }
+        self.try_set_password(password).map_err(|_| ())
+    }
+
+    /// Change this URL’s password.
+    ///
+    /// Like [`Url::set_password`], but return a [`SetterError`] describing
+    /// why the password can not be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{SetterError, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("file://localhost/tmp/foo")?;
+    /// let result = url.try_set_password(Some("secret_password"));
+    /// assert_eq!(result, Err(SetterError::FileWithCredentialsOrPort));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn try_set_password(&mut self, password: Option<&str>) -> Result<(), SetterError> {
+        self.check_can_have_credentials_or_port()?;
         let password = password.unwrap_or_default();
         if !password.is_empty() {
             let host_and_after = self.slice(self.host_start..).to_owned();
@@ -2295,12 +3114,33 @@ impl Url {
     /// # }
     /// # run().unwrap();
     /// ```
+    ///
+    /// See [`Url::try_set_username`] for a variant with a typed error.
     #[allow(clippy::result_unit_err)]
     pub fn set_username(&mut self, username: &str) -> Result<(), ()> {
-        // has_host implies !cannot_be_a_base
-        if !self.has_host() || self.host() == Some(Host::Domain("")) || self.scheme() == "file" {
-            return Err(());
-        }
+        self.try_set_username(username).map_err(|_| ())
+    }
+
+    /// Change this URL’s username.
+    ///
+    /// Like [`Url::set_username`], but return a [`SetterError`] describing
+    /// why the username can not be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use url::{SetterError, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("mailto:rmz@example.com")?;
+    /// assert_eq!(url.try_set_username("user1"), Err(SetterError::CannotBeABase));
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn try_set_username(&mut self, username: &str) -> Result<(), SetterError> {
+        self.check_can_have_credentials_or_port()?;
         let username_start = self.scheme_end + 3;
         debug_assert!(self.slice(self.scheme_end..username_start) == "://");
         if self.slice(username_start..self.username_end) == username {
@@ -2457,25 +3297,56 @@ impl Url {
     /// # }
     /// # run().unwrap();
     /// ```
-    #[allow(clippy::result_unit_err, clippy::suspicious_operation_groupings)]
+    ///
+    /// See [`Url::try_set_scheme`] for a variant with a typed error.
+    #[allow(clippy::result_unit_err)]
     pub fn set_scheme(&mut self, scheme: &str) -> Result<(), ()> {
+        self.try_set_scheme(scheme).map_err(|_| ())
+    }
+
+    /// Change this URL’s scheme.
+    ///
+    /// Like [`Url::set_scheme`], but return a [`SetterError`] describing
+    /// why the scheme can not be changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use url::{SetterError, Url};
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut url = Url::parse("https://example.net")?;
+    /// assert_eq!(url.try_set_scheme("foõ"), Err(SetterError::InvalidScheme));
+    /// assert_eq!(url.try_set_scheme("foo"), Err(SetterError::SpecialSchemeChange));
+    /// assert_eq!(url.try_set_scheme("file"), Err(SetterError::FileSchemeWithAuthority));
+    /// assert_eq!(url.as_str(), "https://example.net/");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn try_set_scheme(&mut self, scheme: &str) -> Result<(), SetterError> {
         let mut parser = Parser::for_setter(String::new());
-        let remaining = parser.parse_scheme(parser::Input::new_no_trim(scheme))?;
+        let remaining = parser
+            .parse_scheme(parser::Input::new_no_trim(scheme))
+            .map_err(|()| SetterError::InvalidScheme)?;
+        if !remaining.is_empty() {
+            return Err(SetterError::InvalidScheme);
+        }
         let new_scheme_type = SchemeType::from(&parser.serialization);
         let old_scheme_type = SchemeType::from(self.scheme());
         // If url’s scheme is a special scheme and buffer is not a special scheme, then return.
-        if (new_scheme_type.is_special() && !old_scheme_type.is_special()) ||
-            // If url’s scheme is not a special scheme and buffer is a special scheme, then return.
-            (!new_scheme_type.is_special() && old_scheme_type.is_special()) ||
-            // If url includes credentials or has a non-null port, and buffer is "file", then return.
-            // If url’s scheme is "file" and its host is an empty host or null, then return.
-            (new_scheme_type.is_file() && self.has_authority())
-        {
-            return Err(());
+        // If url’s scheme is not a special scheme and buffer is a special scheme, then return.
+        if new_scheme_type.is_special() != old_scheme_type.is_special() {
+            return Err(SetterError::SpecialSchemeChange);
         }
-
-        if !remaining.is_empty() || (!self.has_host() && new_scheme_type.is_special()) {
-            return Err(());
+        // If url includes credentials or has a non-null port, and buffer is "file", then return.
+        // If url’s scheme is "file" and its host is an empty host or null, then return.
+        if new_scheme_type.is_file() && self.has_authority() {
+            return Err(SetterError::FileSchemeWithAuthority);
+        }
+        if !self.has_host() && new_scheme_type.is_special() {
+            return Err(SetterError::SpecialSchemeWithoutHost);
         }
         let old_scheme_end = self.scheme_end;
         let new_scheme_end = to_u32(parser.serialization.len()).unwrap();
@@ -2504,7 +3375,7 @@ impl Url {
         // we don't mind it silently failing
         // if there was no port in the first place
         let previous_port = self.port();
-        let _ = self.set_port(previous_port);
+        let _ = self.try_set_port(previous_port);
 
         Ok(())
     }
@@ -2537,6 +3408,15 @@ impl Url {
     /// # }
     /// ```
     ///
+    /// File names do not need to be valid Unicode.
+    /// On Unix-like platforms their bytes are percent-encoded as-is.
+    /// On Windows their UTF-16 code units, which may include unpaired surrogates,
+    /// are converted to [WTF-8](https://simonsapin.github.io/wtf-8/) and then percent-encoded.
+    /// Either way the conversion is lossless:
+    /// for any path `p` accepted by this method,
+    /// `Url::from_file_path(p)?.to_file_path()` returns a path equal to `p`.
+    /// (WASI is an exception: non-Unicode file names are converted lossily there.)
+    ///
     /// This method is only available if the `std` Cargo feature is enabled.
     #[cfg(all(
         feature = "std",
@@ -2672,7 +3552,7 @@ impl Url {
             query_start,
             fragment_start,
         ) = Deserialize::deserialize(deserializer)?;
-        let url = Url {
+        let mut url = Url {
             serialization,
             scheme_end,
             username_end,
@@ -2684,6 +3564,9 @@ impl Url {
             query_start,
             fragment_start,
         };
+        // Versions without opaque hosts serialized them as domains.
+        let scheme = url.serialization.get(..url.scheme_end as usize);
+        url.host = url.host.for_scheme(SchemeType::from(scheme.unwrap_or("")));
         if cfg!(debug_assertions) {
             url.check_invariants().map_err(Error::custom)?
         }
@@ -2707,7 +3590,9 @@ impl Url {
     /// `file:` URLs may have a non-local host),
     /// or if `Path::new_opt()` returns `None`.
     /// (That is, if the percent-decoded path contains a NUL byte or,
-    /// for a Windows path, is not UTF-8.)
+    /// for a Windows path, is not well-formed [WTF-8](https://simonsapin.github.io/wtf-8/).)
+    ///
+    /// This is the inverse of [`Url::from_file_path`], see there for round-trip guarantees.
     ///
     /// This method is only available if the `std` Cargo feature is enabled.
     #[inline]
@@ -2932,7 +3817,12 @@ impl<'de> serde::Deserialize<'de> for Url {
             where
                 E: Error,
             {
-                Url::parse(s).map_err(|err| Error::custom(format!("{err}: {s:?}")))
+                // Accept the zone identifiers that `ParseOptions::ipv6_zone_ids` lets URLs have,
+                // so that every serialized URL can be deserialized.
+                Url::options()
+                    .ipv6_zone_ids(true)
+                    .parse(s)
+                    .map_err(|err| Error::custom(format!("{err}: {s:?}")))
             }
         }
 
@@ -3023,8 +3913,8 @@ fn path_to_file_url_segments_windows(
                 host_end = to_u32(serialization.len()).unwrap();
                 host_internal = host.into();
                 serialization.push('/');
-                let share = share.to_str().ok_or(())?;
-                serialization.extend(percent_encode(share.as_bytes(), PATH_SEGMENT));
+                let share = wtf8::os_str_bytes(share)?;
+                serialization.extend(percent_encode(&share, PATH_SEGMENT));
             }
             _ => return Err(()),
         },
@@ -3038,11 +3928,11 @@ fn path_to_file_url_segments_windows(
         }
 
         path_only_has_prefix = false;
-        // FIXME: somehow work with non-unicode?
-        let component = component.as_os_str().to_str().ok_or(())?;
+        // Non-Unicode file names are encoded as WTF-8
+        let component = wtf8::os_str_bytes(component.as_os_str())?;
 
         serialization.push('/');
-        serialization.extend(percent_encode(component.as_bytes(), PATH_SEGMENT));
+        serialization.extend(percent_encode(&component, PATH_SEGMENT));
     }
 
     // A windows drive letter must end with a slash.
@@ -3129,12 +4019,13 @@ fn file_url_segments_to_pathbuf_windows(
     host: Option<&str>,
     mut segments: str::Split<'_, char>,
 ) -> Result<PathBuf, ()> {
-    use percent_encoding::percent_decode_str;
-    let mut string = String::new();
-    string.try_reserve(estimated_capacity).map_err(|_| ())?;
+    use alloc::vec::Vec;
+    use percent_encoding::percent_decode;
+    let mut bytes = Vec::new();
+    bytes.try_reserve(estimated_capacity).map_err(|_| ())?;
     if let Some(host) = host {
-        string.push_str(r"\\");
-        string.push_str(host);
+        bytes.extend(br"\\");
+        bytes.extend(host.as_bytes());
     } else {
         let first = segments.next().ok_or(())?;
 
@@ -3144,20 +4035,20 @@ fn file_url_segments_to_pathbuf_windows(
                     return Err(());
                 }
 
-                string.push_str(first);
+                bytes.extend(first.as_bytes());
             }
 
             4 => {
                 if !first.starts_with(parser::ascii_alpha) {
                     return Err(());
                 }
-                let bytes = first.as_bytes();
-                if bytes[1] != b'%' || bytes[2] != b'3' || (bytes[3] != b'a' && bytes[3] != b'A') {
+                let first = first.as_bytes();
+                if first[1] != b'%' || first[2] != b'3' || (first[3] != b'a' && first[3] != b'A') {
                     return Err(());
                 }
 
-                string.push_str(&first[0..1]);
-                string.push(':');
+                bytes.push(first[0]);
+                bytes.push(b':');
             }
 
             _ => return Err(()),
@@ -3165,24 +4056,20 @@ fn file_url_segments_to_pathbuf_windows(
     };
 
     for segment in segments {
-        string.push('\\');
-
-        // Currently non-unicode windows paths cannot be represented
-        match percent_decode_str(segment).decode_utf8() {
-            Ok(s) => string.push_str(&s),
-            Err(..) => return Err(()),
-        }
+        bytes.push(b'\\');
+        bytes.extend(percent_decode(segment.as_bytes()));
     }
     // ensure our estimated capacity was good
     if cfg!(test) {
         debug_assert!(
-            string.len() <= estimated_capacity,
+            bytes.len() <= estimated_capacity,
             "len: {}, capacity: {}",
-            string.len(),
+            bytes.len(),
             estimated_capacity
         );
     }
-    let path = PathBuf::from(string);
+    // Non-Unicode file names are decoded from WTF-8
+    let path = PathBuf::from(wtf8::to_os_string(bytes)?);
     debug_assert!(
         path.is_absolute(),
         "to_file_path() failed to produce an absolute Path"
@@ -11,64 +11,46 @@ use alloc::string::String;
 use core::fmt::{self, Formatter, Write};
 use core::str;
 
-use crate::host::{Host, HostInternal};
+use crate::host::{is_ip_future, parse_ipv6addr_with_zone, Host, HostInternal};
 use crate::Url;
 use form_urlencoded::EncodingOverride;
-use percent_encoding::{percent_encode, utf8_percent_encode, AsciiSet, CONTROLS};
+use percent_encoding::{percent_encode, utf8_percent_encode, CONTROLS};
 
-/// https://url.spec.whatwg.org/#fragment-percent-encode-set
-const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
-
-/// https://url.spec.whatwg.org/#query-percent-encode-set
-const QUERY: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
-const SPECIAL_QUERY: &AsciiSet = &QUERY.add(b'\'');
-
-/// https://url.spec.whatwg.org/#path-percent-encode-set
-const PATH: &AsciiSet = &QUERY.add(b'?').add(b'^').add(b'`').add(b'{').add(b'}');
-
-/// https://url.spec.whatwg.org/#userinfo-percent-encode-set
-pub(crate) const USERINFO: &AsciiSet = &PATH
-    .add(b'/')
-    .add(b':')
-    .add(b';')
-    .add(b'=')
-    .add(b'@')
-    .add(b'[')
-    .add(b'\\')
-    .add(b']')
-    .add(b'|');
-
-pub(crate) const PATH_SEGMENT: &AsciiSet = &PATH.add(b'/').add(b'%');
-
-// The backslash (\) character is treated as a path separator in special URLs
-// so it needs to be additionally escaped in that case.
-pub(crate) const SPECIAL_PATH_SEGMENT: &AsciiSet = &PATH_SEGMENT.add(b'\\');
+pub(crate) use percent_encoding::sets::{
+    FRAGMENT, PATH, PATH_SEGMENT, QUERY, SPECIAL_PATH_SEGMENT, SPECIAL_QUERY, USERINFO,
+};
 
 pub type ParseResult<T> = Result<T, ParseError>;
 
 macro_rules! simple_enum_error {
-    ($($name: ident => $description: expr,)+) => {
-        /// Errors that can occur during parsing.
+    ($(#[$attr: meta])* $error: ident { $($name: ident => $description: expr,)+ }) => {
+        $(#[$attr])*
         ///
         /// This may be extended in the future so exhaustive matching is
         /// forbidden.
         #[derive(PartialEq, Eq, Clone, Copy, Debug)]
         #[non_exhaustive]
-        pub enum ParseError {
+        pub enum $error {
             $(
                 $name,
             )+
         }
 
-        impl fmt::Display for ParseError {
+        impl fmt::Display for $error {
             fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
                 match *self {
                     $(
-                        ParseError::$name => fmt.write_str($description),
+                        $error::$name => fmt.write_str($description),
                     )+
                 }
             }
         }
+
+        #[cfg(feature = "std")]
+        impl std::error::Error for $error {}
+
+        #[cfg(not(feature = "std"))]
+        impl core::error::Error for $error {}
     }
 }
 
@@ -78,23 +60,35 @@ macro_rules! ascii_tab_or_new_line_pattern {
     };
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ParseError {}
-
-#[cfg(not(feature = "std"))]
-impl core::error::Error for ParseError {}
+simple_enum_error! {
+    /// Errors that can occur during parsing.
+    ParseError {
+        EmptyHost => "empty host",
+        IdnaError => "invalid international domain name",
+        InvalidPort => "invalid port number",
+        InvalidIpv4Address => "invalid IPv4 address",
+        InvalidIpv6Address => "invalid IPv6 address",
+        InvalidDomainCharacter => "invalid domain character",
+        RelativeUrlWithoutBase => "relative URL without a base",
+        RelativeUrlWithCannotBeABaseBase => "relative URL with a cannot-be-a-base base",
+        SetHostOnCannotBeABaseUrl => "a cannot-be-a-base URL doesn’t have a host to set",
+        Overflow => "URLs more than 4 GB are not supported",
+    }
+}
 
 simple_enum_error! {
-    EmptyHost => "empty host",
-    IdnaError => "invalid international domain name",
-    InvalidPort => "invalid port number",
-    InvalidIpv4Address => "invalid IPv4 address",
-    InvalidIpv6Address => "invalid IPv6 address",
-    InvalidDomainCharacter => "invalid domain character",
-    RelativeUrlWithoutBase => "relative URL without a base",
-    RelativeUrlWithCannotBeABaseBase => "relative URL with a cannot-be-a-base base",
-    SetHostOnCannotBeABaseUrl => "a cannot-be-a-base URL doesn’t have a host to set",
-    Overflow => "URLs more than 4 GB are not supported",
+    /// Reasons why a `Url` setter can reject a change,
+    /// as returned by methods such as [`Url::try_set_port`].
+    SetterError {
+        CannotBeABase => "a cannot-be-a-base URL doesn’t have a host, credentials, port or path segments",
+        NoHost => "a URL without a host or with an empty host can’t have credentials or a port",
+        FileWithCredentialsOrPort => "a file URL can’t have credentials or a port",
+        InvalidScheme => "invalid scheme",
+        SpecialSchemeChange => "can’t change the scheme between special and non-special",
+        FileSchemeWithAuthority => "can’t change the scheme of a URL with an authority to file",
+        SpecialSchemeWithoutHost => "can’t change the scheme of a URL without a host to a special scheme",
+        InvalidHost => "invalid host",
+    }
 }
 
 impl From<::idna::Errors> for ParseError {
@@ -340,6 +334,16 @@ pub struct Parser<'a> {
     pub query_encoding_override: EncodingOverride<'a>,
     pub violation_fn: Option<&'a dyn Fn(SyntaxViolation)>,
     pub context: Context,
+    pub host_options: HostOptions,
+}
+
+/// Opt-in extensions to host parsing, set through `ParseOptions`.
+#[derive(Clone, Copy, Default)]
+pub struct HostOptions {
+    /// Accept RFC 6874 zone identifiers after IPv6 addresses.
+    pub ipv6_zone_ids: bool,
+    /// Accept RFC 3986 IPvFuture literals as the host of non-special URLs.
+    pub ip_future: bool,
 }
 
 #[derive(PartialEq, Eq, Copy, Clone)]
@@ -371,6 +375,7 @@ impl Parser<'_> {
             query_encoding_override: None,
             violation_fn: None,
             context: Context::Setter,
+            host_options: HostOptions::default(),
         }
     }
 
@@ -956,8 +961,16 @@ impl Parser<'_> {
         scheme_end: u32,
         scheme_type: SchemeType,
     ) -> ParseResult<(u32, HostInternal, Option<u16>, Input<'i>)> {
-        let (host, remaining) = Parser::parse_host(input, scheme_type)?;
+        let (host, zone, remaining) =
+            Parser::parse_host_with_options(input, scheme_type, self.host_options)?;
         write!(&mut self.serialization, "{host}").unwrap();
+        if let Some(zone) = zone {
+            // Insert the zone identifier before the closing bracket.
+            self.serialization.pop();
+            self.serialization.push_str("%25");
+            self.serialization.push_str(&zone);
+            self.serialization.push(']');
+        }
         let host_end = to_u32(self.serialization.len())?;
         if let Host::Domain(h) = &host {
             if h.is_empty() {
@@ -984,15 +997,29 @@ impl Parser<'_> {
         } else {
             (None, remaining)
         };
-        Ok((host_end, host.into(), port, remaining))
+        let host = HostInternal::from(host).for_scheme(scheme_type);
+        Ok((host_end, host, port, remaining))
     }
 
     pub fn parse_host(
-        mut input: Input<'_>,
+        input: Input<'_>,
         scheme_type: SchemeType,
     ) -> ParseResult<(Host<Cow<'_, str>>, Input<'_>)> {
+        let (host, _, remaining) =
+            Parser::parse_host_with_options(input, scheme_type, HostOptions::default())?;
+        Ok((host, remaining))
+    }
+
+    /// Like `parse_host`, with the extensions enabled in `options`.
+    /// The zone identifier of an IPv6 address is returned separately.
+    pub fn parse_host_with_options(
+        mut input: Input<'_>,
+        scheme_type: SchemeType,
+        options: HostOptions,
+    ) -> ParseResult<(Host<Cow<'_, str>>, Option<String>, Input<'_>)> {
         if scheme_type.is_file() {
-            return Parser::get_file_host(input);
+            let (host, remaining) = Parser::get_file_host(input)?;
+            return Ok((host, None, remaining));
         }
         // Undo the Input abstraction here to avoid allocating in the common case
         // where the host part of the input does not contain any tab or newline
@@ -1034,12 +1061,22 @@ impl Parser<'_> {
         if scheme_type == SchemeType::SpecialNotFile && host_str.is_empty() {
             return Err(ParseError::EmptyHost);
         }
+        if host_str.starts_with('[') && host_str.ends_with(']') {
+            let literal = &host_str[1..host_str.len() - 1];
+            if options.ip_future && !scheme_type.is_special() && is_ip_future(literal) {
+                return Ok((Host::Domain(host_str), None, input));
+            }
+            if options.ipv6_zone_ids {
+                let (address, zone) = parse_ipv6addr_with_zone(literal)?;
+                return Ok((Host::Ipv6(address), zone.map(String::from), input));
+            }
+        }
         if !scheme_type.is_special() {
             let host = Host::parse_opaque_cow(host_str)?;
-            return Ok((host, input));
+            return Ok((host, None, input));
         }
         let host = Host::parse_cow(host_str)?;
-        Ok((host, input))
+        Ok((host, None, input))
     }
 
     fn get_file_host(input: Input<'_>) -> ParseResult<(Host<Cow<'_, str>>, Input<'_>)> {
@@ -1315,8 +1352,7 @@ impl Parser<'_> {
             };
             match segment_before_slash {
                 // If buffer is a double-dot path segment, shorten url’s path,
-                ".." | "%2e%2e" | "%2e%2E" | "%2E%2e" | "%2E%2E" | "%2e." | "%2E." | ".%2e"
-                | ".%2E" => {
+                _ if is_double_dot_path_segment(segment_before_slash) => {
                     debug_assert!(self.serialization.as_bytes()[segment_start - 1] == b'/');
                     self.serialization.truncate(segment_start);
                     if self.serialization.ends_with('/')
@@ -1333,7 +1369,7 @@ impl Parser<'_> {
                 }
                 // Otherwise, if buffer is a single-dot path segment and if neither c is U+002F (/),
                 // nor url is special and c is U+005C (\), append the empty string to url’s path.
-                "." | "%2e" | "%2E" => {
+                _ if is_single_dot_path_segment(segment_before_slash) => {
                     self.serialization.truncate(segment_start);
                     if !self.serialization.ends_with('/') {
                         self.serialization.push('/');
@@ -1766,10 +1802,27 @@ pub fn to_u32(i: usize) -> ParseResult<u32> {
     }
 }
 
-fn is_normalized_windows_drive_letter(segment: &str) -> bool {
+pub(crate) fn is_normalized_windows_drive_letter(segment: &str) -> bool {
     is_windows_drive_letter(segment) && segment.as_bytes()[1] == b':'
 }
 
+/// https://url.spec.whatwg.org/#single-dot-path-segment
+pub(crate) fn is_single_dot_path_segment(segment: &str) -> bool {
+    segment == "." || segment.eq_ignore_ascii_case("%2e")
+}
+
+/// https://url.spec.whatwg.org/#double-dot-path-segment
+pub(crate) fn is_double_dot_path_segment(segment: &str) -> bool {
+    let rest = match segment.strip_prefix('.') {
+        Some(rest) => rest,
+        None => match segment.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("%2e") => &segment[3..],
+            _ => return false,
+        },
+    };
+    is_single_dot_path_segment(rest)
+}
+
 /// Whether the scheme is file:, the path has a single segment, and that segment
 /// is a Windows drive letter
 #[inline]
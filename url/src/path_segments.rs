@@ -8,8 +8,13 @@
 
 use crate::parser::{self, to_u32, SchemeType};
 use crate::Url;
+use alloc::borrow::Cow;
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::ops::{Bound, Range, RangeBounds};
 use core::str;
+use percent_encoding::percent_decode_str;
 
 /// Exposes methods to manipulate the path of a URL that is not cannot-be-base.
 ///
@@ -69,6 +74,7 @@ pub fn new(url: &mut Url) -> PathSegmentsMut<'_> {
 
 impl Drop for PathSegmentsMut<'_> {
     fn drop(&mut self) {
+        self.url.update_path_prefix();
         self.url
             .restore_after_path(self.old_after_path_position, &self.after_path)
     }
@@ -172,6 +178,320 @@ impl PathSegmentsMut<'_> {
         self.extend(Some(segment))
     }
 
+    /// Insert a segment at position `index` of this URL’s path,
+    /// shifting the following segments.
+    ///
+    /// The segment is percent-encoded like with `push`,
+    /// and `"."` or `".."` segments are likewise ignored.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of segments.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let mut url = Url::parse("https://example.net/users/42?tab=1")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .insert(0, "api").insert(1, "v2");
+    /// assert_eq!(url.as_str(), "https://example.net/api/v2/users/42?tab=1");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn insert(&mut self, index: usize, segment: &str) -> &mut Self {
+        self.splice(index..index, Some(segment))
+    }
+
+    /// Remove the segment at position `index` of this URL’s path.
+    ///
+    /// If it was the only segment, the path becomes `"/"`.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than the number of segments.
+    pub fn remove(&mut self, index: usize) -> &mut Self {
+        self.splice(index..=index, None::<&str>)
+    }
+
+    /// Replace the segment at position `index` of this URL’s path.
+    ///
+    /// The segment is percent-encoded like with `push`.
+    /// Replacing with `"."` or `".."` removes the segment, since these are ignored.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than the number of segments.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let mut url = Url::parse("https://example.net/v1/users/42#profile")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .replace(0, "v2");
+    /// assert_eq!(url.as_str(), "https://example.net/v2/users/42#profile");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn replace(&mut self, index: usize, segment: &str) -> &mut Self {
+        self.splice(index..=index, Some(segment))
+    }
+
+    /// Keep the first `len` segments of this URL’s path and remove the others.
+    ///
+    /// Does nothing if the path has `len` segments or fewer.
+    /// `truncate(0)` is equivalent to `clear()`.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    pub fn truncate(&mut self, len: usize) -> &mut Self {
+        let count = self.segment_ranges().len();
+        if len < count {
+            self.splice(len..count, None::<&str>);
+        }
+        self
+    }
+
+    /// Replace the segments in `range` of this URL’s path with the given segments,
+    /// which are percent-encoded like with `extend`.
+    ///
+    /// The path keeps at least one segment:
+    /// if all segments are removed and none are inserted, the path becomes `"/"`.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end,
+    /// or if its end is greater than the number of segments.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let mut url = Url::parse("https://example.net/a/b/c/d?q")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .splice(1..3, ["x y", "z"]);
+    /// assert_eq!(url.as_str(), "https://example.net/a/x%20y/z/d?q");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn splice<R, I>(&mut self, range: R, segments: I) -> &mut Self
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let ranges = self.segment_ranges();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => ranges.len(),
+        };
+        assert!(
+            start <= end,
+            "segment range starts at {} but ends at {}",
+            start,
+            end
+        );
+        assert!(
+            end <= ranges.len(),
+            "segment range end {} is out of range for a path of {} segments",
+            end,
+            ranges.len()
+        );
+        // Byte offsets of the slash before the first replaced segment,
+        // and of the slash after the last one.
+        let prefix_end = match ranges.get(start) {
+            Some(range) => range.start - 1,
+            None => self.url.serialization.len(),
+        };
+        let suffix_start = match end.checked_sub(1) {
+            Some(last) if start < end => ranges[last].end,
+            _ => prefix_end,
+        };
+        let suffix = String::from(&self.url.serialization[suffix_start..]);
+        self.url.serialization.truncate(prefix_end);
+
+        let scheme_type = SchemeType::from(self.url.scheme());
+        let path_start = self.url.path_start as usize;
+        self.url.mutate(|parser| {
+            parser.context = parser::Context::PathSegmentSetter;
+            for segment in segments {
+                let segment = segment.as_ref();
+                if matches!(segment, "." | "..") {
+                    continue;
+                }
+                parser.serialization.push('/');
+                let mut has_host = true;
+                parser.parse_path(
+                    scheme_type,
+                    &mut has_host,
+                    path_start,
+                    parser::Input::new_no_trim(segment),
+                );
+            }
+        });
+        self.url.serialization.push_str(&suffix);
+        if self.url.serialization.len() == path_start && !ranges.is_empty() {
+            self.url.serialization.push('/');
+        }
+        self
+    }
+
+    /// Keep only the segments of this URL’s path for which `f` returns `true`.
+    ///
+    /// `f` is given each segment in its percent-encoded form, in order.
+    /// If no segment is kept, the path becomes `"/"`.
+    /// An empty path, which only non-special URLs can have, is left as-is.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let mut url = Url::parse("https://example.net//a///b/")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .retain(|segment| !segment.is_empty());
+    /// assert_eq!(url.as_str(), "https://example.net/a/b");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F) -> &mut Self
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let ranges = self.segment_ranges();
+        if ranges.is_empty() {
+            return self;
+        }
+        let path_start = self.url.path_start as usize;
+        let mut path = String::with_capacity(self.url.serialization.len() - path_start);
+        for range in ranges {
+            let segment = &self.url.serialization[range];
+            if f(segment) {
+                path.push('/');
+                path.push_str(segment);
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        self.url.serialization.truncate(path_start);
+        self.url.serialization.push_str(&path);
+        self
+    }
+
+    /// Return the byte ranges of the segments of the path in the serialization.
+    fn segment_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        // Non special url's path might be empty, without any segment
+        if self.url.serialization.len() < self.after_first_slash {
+            return ranges;
+        }
+        let mut start = self.after_first_slash;
+        for segment in self.url.serialization[self.after_first_slash..].split('/') {
+            ranges.push(start..start + segment.len());
+            start += segment.len() + 1;
+        }
+        ranges
+    }
+
+    /// Append the given segment at the end of this URL’s path, resolving dot segments.
+    ///
+    /// A `"."` segment leaves the path unchanged and a `".."` segment removes the last segment,
+    /// like `pop` but without removing the drive letter of a `file:` URL.
+    /// The percent-encoded variants that `Url::parse` recognizes,
+    /// such as `"%2e"` or `".%2E"`, are resolved the same way.
+    /// Other segments are appended like with `push`.
+    ///
+    /// Returns `&mut Self` so that method calls can be chained.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use url::Url;
+    ///
+    /// # #[cfg(feature = "std")]
+    /// # use std::error::Error;
+    /// # #[cfg(not(feature = "std"))]
+    /// # use core::error::Error;
+    ///
+    /// # fn run() -> Result<(), Box<dyn Error>> {
+    /// let mut url = Url::parse("https://example.net/static/css")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .push_resolving("..").push_resolving("%2e").push_resolving("img");
+    /// assert_eq!(url.as_str(), "https://example.net/static/img");
+    ///
+    /// let mut url = Url::parse("file:///C:/")?;
+    /// url.path_segments_mut().map_err(|_| "cannot be base")?
+    ///     .pop_if_empty().push_resolving("..");
+    /// assert_eq!(url.as_str(), "file:///C:");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn push_resolving(&mut self, segment: &str) -> &mut Self {
+        if parser::is_single_dot_path_segment(segment) {
+            self
+        } else if parser::is_double_dot_path_segment(segment) {
+            if SchemeType::from(self.url.scheme()).is_file()
+                && parser::is_normalized_windows_drive_letter(
+                    &self.url.serialization[self.after_first_slash..],
+                )
+            {
+                return self;
+            }
+            self.pop()
+        } else {
+            self.push(segment)
+        }
+    }
+
     /// Append each segment from the given iterator at the end of this URL’s path.
     ///
     /// Each segment is percent-encoded like in `Url::parse` or `Url::join`,
@@ -265,3 +585,206 @@ impl PathSegmentsMut<'_> {
         self
     }
 }
+
+/// Remove the dot segments of a path, as specified by
+/// [RFC 3986 section 5.2.4](https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4).
+///
+/// Besides `.` and `..`, the percent-encoded variants of dot segments that `Url::parse`
+/// recognizes are removed: `%2e` for `.`, and `.%2e`, `%2e.` and `%2e%2e` for `..`,
+/// with ASCII case-insensitive hexadecimal digits.
+///
+/// Unlike `Url::parse`, this does not treat backslashes as separators,
+/// nor keep Windows drive letters of `file:` URLs.
+/// The path is returned unchanged, without copying, if it does not contain dot segments.
+///
+/// # Examples
+///
+/// ```rust
+/// use url::remove_dot_segments;
+///
+/// assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+/// assert_eq!(remove_dot_segments("mid/content=5/../6"), "mid/6");
+/// assert_eq!(remove_dot_segments("/a/%2E%2e/b/c/.%2e"), "/b/");
+/// assert_eq!(remove_dot_segments("../../a"), "a");
+/// ```
+pub fn remove_dot_segments(path: &str) -> Cow<'_, str> {
+    let is_dot_segment = |segment| {
+        parser::is_single_dot_path_segment(segment) || parser::is_double_dot_path_segment(segment)
+    };
+    if !path.split('/').any(is_dot_segment) {
+        return path.into();
+    }
+    let mut output = String::with_capacity(path.len());
+    let mut input = path;
+    while !input.is_empty() {
+        let (has_slash, rest) = match input.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        let (segment, rest) = match rest.find('/') {
+            Some(end) => rest.split_at(end),
+            None => (rest, ""),
+        };
+        let is_double_dot = parser::is_double_dot_path_segment(segment);
+        if !is_double_dot && !parser::is_single_dot_path_segment(segment) {
+            // E. Move the first path segment to the output buffer.
+            if has_slash {
+                output.push('/');
+            }
+            output.push_str(segment);
+            input = rest;
+            continue;
+        }
+        if !has_slash {
+            // A. and D. Remove a leading "../", "./", ".." or ".".
+            input = rest.strip_prefix('/').unwrap_or(rest);
+            continue;
+        }
+        // B. and C. Replace a leading "/./", "/.", "/../" or "/.." with "/".
+        if is_double_dot {
+            output.truncate(output.rfind('/').unwrap_or(0));
+        }
+        input = if rest.is_empty() { "/" } else { rest };
+    }
+    output.into()
+}
+
+/// Percent-encoded path segments, split from both ends.
+#[derive(Clone, Debug)]
+struct Segments<'a> {
+    remaining: &'a str,
+    len: usize,
+}
+
+impl<'a> Segments<'a> {
+    /// `path` is a path without its initial slash.
+    fn new(path: &'a str) -> Self {
+        Segments {
+            remaining: path,
+            len: path.bytes().filter(|&b| b == b'/').count() + 1,
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self.len {
+            0 => None,
+            1 => {
+                self.len = 0;
+                Some(self.remaining)
+            }
+            _ => {
+                self.len -= 1;
+                let (segment, rest) = self.remaining.split_once('/').unwrap();
+                self.remaining = rest;
+                Some(segment)
+            }
+        }
+    }
+
+    fn next_back(&mut self) -> Option<&'a str> {
+        match self.len {
+            0 => None,
+            1 => {
+                self.len = 0;
+                Some(self.remaining)
+            }
+            _ => {
+                self.len -= 1;
+                let (rest, segment) = self.remaining.rsplit_once('/').unwrap();
+                self.remaining = rest;
+                Some(segment)
+            }
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<&'a str> {
+        for _ in 0..n.min(self.len) {
+            self.next();
+        }
+        self.next()
+    }
+}
+
+/// An iterator of the percent-decoded path segments of a URL,
+/// as returned by [`Url::path_segments_decoded`].
+///
+/// Segments that are not valid UTF-8 once decoded are returned as a `Utf8Error`.
+#[derive(Clone, Debug)]
+pub struct PathSegmentsDecoded<'a>(Segments<'a>);
+
+/// An iterator of the percent-decoded path segments of a URL,
+/// as returned by [`Url::path_segments_decoded_lossy`].
+///
+/// Invalid UTF-8 in decoded segments is replaced with U+FFFD REPLACEMENT CHARACTER.
+#[derive(Clone, Debug)]
+pub struct PathSegmentsDecodedLossy<'a>(Segments<'a>);
+
+// Not re-exported outside the crate
+pub fn decoded(path: &str) -> PathSegmentsDecoded<'_> {
+    PathSegmentsDecoded(Segments::new(path))
+}
+
+// Not re-exported outside the crate
+pub fn decoded_lossy(path: &str) -> PathSegmentsDecodedLossy<'_> {
+    PathSegmentsDecodedLossy(Segments::new(path))
+}
+
+fn decode(segment: &str) -> Result<Cow<'_, str>, str::Utf8Error> {
+    percent_decode_str(segment).decode_utf8()
+}
+
+fn decode_lossy(segment: &str) -> Cow<'_, str> {
+    percent_decode_str(segment).decode_utf8_lossy()
+}
+
+impl<'a> Iterator for PathSegmentsDecoded<'a> {
+    type Item = Result<Cow<'a, str>, str::Utf8Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(decode)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.0.nth(n).map(decode)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl DoubleEndedIterator for PathSegmentsDecoded<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(decode)
+    }
+}
+
+impl ExactSizeIterator for PathSegmentsDecoded<'_> {}
+
+impl FusedIterator for PathSegmentsDecoded<'_> {}
+
+impl<'a> Iterator for PathSegmentsDecodedLossy<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(decode_lossy)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.0.nth(n).map(decode_lossy)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl DoubleEndedIterator for PathSegmentsDecodedLossy<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(decode_lossy)
+    }
+}
+
+impl ExactSizeIterator for PathSegmentsDecodedLossy<'_> {}
+
+impl FusedIterator for PathSegmentsDecodedLossy<'_> {}
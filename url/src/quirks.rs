@@ -248,11 +248,8 @@ pub fn port(url: &Url) -> &str {
 pub fn set_port(url: &mut Url, new_port: &str) -> Result<(), ()> {
     let result;
     {
-        // has_host implies !cannot_be_a_base
+        url.check_can_have_credentials_or_port().map_err(|_| ())?;
         let scheme = url.scheme();
-        if !url.has_host() || url.host() == Some(Host::Domain("")) || scheme == "file" {
-            return Err(());
-        }
         result = Parser::parse_port(
             Input::new_no_trim(new_port),
             || default_port(scheme),
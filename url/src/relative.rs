@@ -0,0 +1,451 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::parser::{FRAGMENT, PATH, QUERY};
+use crate::{Host, ParseError};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::{cmp, fmt, hash, str};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// Characters encoded by `RelativeUrl::set_path` when the reference has a scheme
+/// but no authority, so that the path may be opaque.
+const OPAQUE_PATH: &AsciiSet = &CONTROLS.add(b' ').add(b'#').add(b'?');
+
+/// A URL reference that does not need a base URL to be parsed,
+/// such as `../a?b`, `//example.net/` or `#top`.
+///
+/// A reference is split into an optional scheme, an optional authority,
+/// a path, an optional query and an optional fragment,
+/// and is resolved with [`Url::join_relative`](crate::Url::join_relative).
+///
+/// Parsing removes leading and trailing C0 control characters and spaces,
+/// removes ASCII tabs and newlines,
+/// and percent-encodes the path, query and fragment with the same sets as `Url::parse`.
+/// The path of a reference with a scheme but no authority, such as `mailto:a b@example.net`,
+/// is only stripped of control characters since it may be an opaque path.
+/// What depends on the base URL is left to `join_relative`:
+/// domains are not normalized, and backslashes are kept as written since they are only
+/// path separators when resolved against a special URL.
+/// As a result, `base.join_relative(&RelativeUrl::parse(input)?)`
+/// returns the same as `base.join(input)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use url::{RelativeUrl, Url};
+/// # use url::ParseError;
+///
+/// # fn run() -> Result<(), ParseError> {
+/// let mut reference = RelativeUrl::parse("../a b?c=d")?;
+/// assert_eq!(reference.path(), "../a%20b");
+/// assert_eq!(reference.query(), Some("c=d"));
+///
+/// reference.set_query(Some("e=f"));
+/// assert_eq!(reference.as_str(), "../a%20b?e=f");
+///
+/// let base = Url::parse("https://example.net/x/y/z")?;
+/// let url = base.join_relative(&reference)?;
+/// assert_eq!(url.as_str(), "https://example.net/x/a%20b?e=f");
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RelativeUrl {
+    serialization: String,
+    /// Index of the `:` after the scheme.
+    scheme_end: Option<usize>,
+    /// Index after the `//` before the authority.
+    authority_start: Option<usize>,
+    path_start: usize,
+    /// Index of the `?` before the query.
+    query_start: Option<usize>,
+    /// Index of the `#` before the fragment.
+    fragment_start: Option<usize>,
+}
+
+impl RelativeUrl {
+    /// Parse a URL reference.
+    ///
+    /// # Errors
+    ///
+    /// Return an error if the reference has an authority with an invalid port number
+    /// or an invalid IPv6 address.
+    /// Other errors depend on the base URL and are only reported by
+    /// [`Url::join_relative`](crate::Url::join_relative).
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let input = input.trim_matches(|c| c <= ' ');
+        let input: Cow<'_, str> = if input.contains(['\t', '\n', '\r']) {
+            input
+                .chars()
+                .filter(|&c| !matches!(c, '\t' | '\n' | '\r'))
+                .collect::<String>()
+                .into()
+        } else {
+            input.into()
+        };
+        let input = &*input;
+
+        let scheme_end = find_scheme_end(input);
+        let after_scheme = scheme_end.map_or(0, |end| end + 1);
+        let authority_start = if input[after_scheme..].starts_with("//") {
+            Some(after_scheme + 2)
+        } else {
+            None
+        };
+        let path_start = match authority_start {
+            Some(start) => start + find_or_len(&input[start..], &['/', '?', '#']),
+            None => after_scheme,
+        };
+        let path_end = path_start + find_or_len(&input[path_start..], &['?', '#']);
+        let fragment_start = input[path_end..].find('#').map(|i| path_end + i);
+        let has_query = input[path_end..].starts_with('?');
+        if let Some(start) = authority_start {
+            check_authority(&input[start..path_start])?;
+        }
+
+        // Without an authority, the path of a reference with a scheme may be opaque.
+        let path_set = if scheme_end.is_some() && authority_start.is_none() {
+            CONTROLS
+        } else {
+            PATH
+        };
+        let mut serialization = String::with_capacity(input.len());
+        serialization.push_str(&input[..path_start]);
+        serialization.extend(utf8_percent_encode(&input[path_start..path_end], path_set));
+        let mut query_start = None;
+        if has_query {
+            let query_end = fragment_start.unwrap_or(input.len());
+            query_start = Some(serialization.len());
+            serialization.push('?');
+            serialization.extend(utf8_percent_encode(&input[path_end + 1..query_end], QUERY));
+        }
+        let fragment_start = fragment_start.map(|start| {
+            let new_start = serialization.len();
+            serialization.push('#');
+            serialization.extend(utf8_percent_encode(&input[start + 1..], FRAGMENT));
+            new_start
+        });
+        Ok(RelativeUrl {
+            serialization,
+            scheme_end,
+            authority_start,
+            path_start,
+            query_start,
+            fragment_start,
+        })
+    }
+
+    /// Return the serialization of this reference.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.serialization
+    }
+
+    /// Return the scheme of this reference, if any, as written.
+    #[inline]
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme_end.map(|end| &self.serialization[..end])
+    }
+
+    /// Return the authority of this reference, if any, without the leading `//`.
+    ///
+    /// The authority is `username:password@host:port`, where everything but the host is optional.
+    #[inline]
+    pub fn authority(&self) -> Option<&str> {
+        self.authority_start
+            .map(|start| &self.serialization[start..self.path_start])
+    }
+
+    /// Return the path of this reference, percent-encoded. It may be empty.
+    pub fn path(&self) -> &str {
+        let end = self
+            .query_start
+            .or(self.fragment_start)
+            .unwrap_or(self.serialization.len());
+        &self.serialization[self.path_start..end]
+    }
+
+    /// Return the query string of this reference, if any, percent-encoded.
+    pub fn query(&self) -> Option<&str> {
+        self.query_start.map(|start| {
+            let end = self.fragment_start.unwrap_or(self.serialization.len());
+            &self.serialization[start + 1..end]
+        })
+    }
+
+    /// Return the fragment identifier of this reference, if any, percent-encoded.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment_start
+            .map(|start| &self.serialization[start + 1..])
+    }
+
+    /// Change the path of this reference.
+    ///
+    /// `?` and `#` and other characters that `Url::parse` would encode are percent-encoded.
+    /// If needed, the path is prefixed with `/`, `./` or `/.`
+    /// so that it is not mistaken for an authority or a scheme,
+    /// and that it is separated from the authority.
+    ///
+    /// ```rust
+    /// use url::RelativeUrl;
+    /// # use url::ParseError;
+    ///
+    /// # fn run() -> Result<(), ParseError> {
+    /// let mut reference = RelativeUrl::parse("a?b")?;
+    /// reference.set_path("c:d");
+    /// assert_eq!(reference.as_str(), "./c:d?b");
+    ///
+    /// let mut reference = RelativeUrl::parse("//example.net")?;
+    /// reference.set_path("e#f");
+    /// assert_eq!(reference.as_str(), "//example.net/e%23f");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn set_path(&mut self, path: &str) {
+        let set = if self.scheme_end.is_some() && self.authority_start.is_none() {
+            OPAQUE_PATH
+        } else {
+            PATH
+        };
+        let path: Cow<'_, str> = utf8_percent_encode(path, set).into();
+        let prefix = if self.authority_start.is_some() {
+            if path.is_empty() || path.starts_with('/') {
+                ""
+            } else {
+                "/"
+            }
+        } else if path.starts_with("//") {
+            "/."
+        } else if self.scheme_end.is_none() && path.split('/').next().unwrap().contains(':') {
+            "./"
+        } else {
+            ""
+        };
+        let suffix_start = self
+            .query_start
+            .or(self.fragment_start)
+            .unwrap_or(self.serialization.len());
+        let mut serialization = String::from(&self.serialization[..self.path_start]);
+        serialization.push_str(prefix);
+        serialization.push_str(&path);
+        serialization.push_str(&self.serialization[suffix_start..]);
+        self.reparse(&serialization)
+    }
+
+    /// Change the query string of this reference.
+    ///
+    /// Characters that `Url::parse` would encode in a query, including `#`, are percent-encoded.
+    pub fn set_query(&mut self, query: Option<&str>) {
+        let query_start = self
+            .query_start
+            .or(self.fragment_start)
+            .unwrap_or(self.serialization.len());
+        let suffix_start = self.fragment_start.unwrap_or(self.serialization.len());
+        let mut serialization = String::from(&self.serialization[..query_start]);
+        if let Some(query) = query {
+            serialization.push('?');
+            serialization.extend(utf8_percent_encode(query, QUERY));
+        }
+        serialization.push_str(&self.serialization[suffix_start..]);
+        self.reparse(&serialization)
+    }
+
+    /// Change the fragment identifier of this reference.
+    ///
+    /// Characters that `Url::parse` would encode in a fragment are percent-encoded.
+    pub fn set_fragment(&mut self, fragment: Option<&str>) {
+        let fragment_start = self.fragment_start.unwrap_or(self.serialization.len());
+        let mut serialization = String::from(&self.serialization[..fragment_start]);
+        if let Some(fragment) = fragment {
+            serialization.push('#');
+            serialization.extend(utf8_percent_encode(fragment, FRAGMENT));
+        }
+        self.reparse(&serialization)
+    }
+
+    fn reparse(&mut self, serialization: &str) {
+        // The authority is unchanged and the new components are already percent-encoded,
+        // so parsing succeeds and does not change the serialization.
+        *self = Self::parse(serialization).expect("a valid reference");
+        debug_assert_eq!(self.serialization, serialization);
+    }
+}
+
+/// Return the index of the `:` after a scheme at the start of `input`, if any.
+fn find_scheme_end(input: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    if !bytes.first()?.is_ascii_alphabetic() {
+        return None;
+    }
+    let end = bytes
+        .iter()
+        .position(|&b| !(b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')))?;
+    if bytes[end] == b':' {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+fn find_or_len(input: &str, delimiters: &[char]) -> usize {
+    input.find(delimiters).unwrap_or(input.len())
+}
+
+/// Check the parts of an authority whose validity does not depend on the base URL.
+fn check_authority(authority: &str) -> Result<(), ParseError> {
+    let host_and_port = match authority.rfind('@') {
+        Some(at) => &authority[at + 1..],
+        None => authority,
+    };
+    let port = if host_and_port.starts_with('[') {
+        let end = match host_and_port.find(']') {
+            Some(end) => end,
+            None => return Err(ParseError::InvalidIpv6Address),
+        };
+        Host::parse(&host_and_port[..=end])?;
+        match host_and_port[end + 1..].strip_prefix(':') {
+            Some(port) => port,
+            None if end + 1 == host_and_port.len() => "",
+            None => return Err(ParseError::InvalidIpv6Address),
+        }
+    } else {
+        host_and_port.split_once(':').map_or("", |(_, port)| port)
+    };
+    if !port.bytes().all(|b| b.is_ascii_digit())
+        || port.parse::<u32>().map_or(false, |p| p > 0xFFFF)
+    {
+        return Err(ParseError::InvalidPort);
+    }
+    Ok(())
+}
+
+/// Parse a string as a URL reference.
+impl str::FromStr for RelativeUrl {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Self::parse(input)
+    }
+}
+
+/// Display the serialization of this reference.
+impl fmt::Display for RelativeUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.serialization, formatter)
+    }
+}
+
+/// Debug the components of this reference.
+impl fmt::Debug for RelativeUrl {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RelativeUrl")
+            .field("scheme", &self.scheme())
+            .field("authority", &self.authority())
+            .field("path", &self.path())
+            .field("query", &self.query())
+            .field("fragment", &self.fragment())
+            .finish()
+    }
+}
+
+/// References compare like their serialization.
+impl Eq for RelativeUrl {}
+
+/// References compare like their serialization.
+impl PartialEq for RelativeUrl {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.serialization == other.serialization
+    }
+}
+
+/// References compare like their serialization.
+impl Ord for RelativeUrl {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.serialization.cmp(&other.serialization)
+    }
+}
+
+/// References compare like their serialization.
+impl PartialOrd for RelativeUrl {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// References hash like their serialization.
+impl hash::Hash for RelativeUrl {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(&self.serialization, state)
+    }
+}
+
+/// Return the serialization of this reference.
+impl AsRef<str> for RelativeUrl {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.serialization
+    }
+}
+
+/// Serializes this reference into a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RelativeUrl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes this reference from a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RelativeUrl {
+    fn deserialize<D>(deserializer: D) -> Result<RelativeUrl, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Visitor};
+
+        struct RelativeUrlVisitor;
+
+        impl Visitor<'_> for RelativeUrlVisitor {
+            type Value = RelativeUrl;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string representing a URL reference")
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                RelativeUrl::parse(s).map_err(|err| Error::custom(format!("{err}: {s:?}")))
+            }
+        }
+
+        deserializer.deserialize_str(RelativeUrlVisitor)
+    }
+}
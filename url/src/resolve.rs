@@ -0,0 +1,222 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Pluggable host name resolution for [`Url::resolve_with`](crate::Url::resolve_with)
+//! and [`Url::resolve_with_async`](crate::Url::resolve_with_async).
+//!
+//! Resolvers are only asked about domains:
+//! URLs with an IPv4 or IPv6 address as their host are turned into socket addresses directly.
+//! The opaque hosts of non-special URLs are passed on like domains.
+
+use crate::net::{IpAddr, SocketAddr};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::future::{self, Future};
+use std::io;
+
+/// A blocking host name resolver.
+pub trait Resolver {
+    /// Return the socket addresses for `domain` with the given port number.
+    ///
+    /// `domain` is in the ASCII form returned by [`Url::host_str`](crate::Url::host_str).
+    fn resolve(&self, domain: &str, port: u16) -> io::Result<Vec<SocketAddr>>;
+}
+
+impl<R: Resolver + ?Sized> Resolver for &R {
+    fn resolve(&self, domain: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        (**self).resolve(domain, port)
+    }
+}
+
+/// An asynchronous host name resolver.
+///
+/// The returned future must not borrow `domain`,
+/// implementations typically copy it into the future.
+pub trait AsyncResolver {
+    /// The future returned by [`AsyncResolver::resolve`].
+    type Future: Future<Output = io::Result<Vec<SocketAddr>>>;
+
+    /// Start resolving `domain` with the given port number.
+    ///
+    /// `domain` is in the ASCII form returned by [`Url::host_str`](crate::Url::host_str).
+    fn resolve(&self, domain: &str, port: u16) -> Self::Future;
+}
+
+impl<R: AsyncResolver + ?Sized> AsyncResolver for &R {
+    type Future = R::Future;
+
+    fn resolve(&self, domain: &str, port: u16) -> R::Future {
+        (**self).resolve(domain, port)
+    }
+}
+
+/// The resolver of the operating system, as used by `std::net::ToSocketAddrs`.
+///
+/// This is the resolver used by [`Url::socket_addrs`](crate::Url::socket_addrs).
+#[cfg(any(
+    unix,
+    windows,
+    target_os = "redox",
+    target_os = "wasi",
+    target_os = "hermit"
+))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemResolver;
+
+#[cfg(any(
+    unix,
+    windows,
+    target_os = "redox",
+    target_os = "wasi",
+    target_os = "hermit"
+))]
+impl Resolver for SystemResolver {
+    fn resolve(&self, domain: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        use crate::net::ToSocketAddrs;
+        Ok((domain, port).to_socket_addrs()?.collect())
+    }
+}
+
+/// Return the scope ID of an IPv6 zone identifier,
+/// either a decimal number or a network interface name.
+pub(crate) fn ipv6_scope_id(zone: &str) -> io::Result<u32> {
+    let zone = percent_encoding::percent_decode_str(zone).decode_utf8_lossy();
+    if let Ok(scope_id) = zone.parse() {
+        return Ok(scope_id);
+    }
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::os::raw::{c_char, c_uint};
+
+        extern "C" {
+            fn if_nametoindex(ifname: *const c_char) -> c_uint;
+        }
+
+        if let Ok(name) = CString::new(zone.as_bytes()) {
+            // SAFETY: `name` is a valid NUL-terminated string for the duration of the call.
+            let index = unsafe { if_nametoindex(name.as_ptr()) };
+            if index != 0 {
+                return Ok(index);
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Unknown network interface in IPv6 zone identifier",
+        ))
+    }
+    #[cfg(not(unix))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "IPv6 zone identifiers must be numeric on this platform",
+        ))
+    }
+}
+
+/// A resolver that looks up domains in a fixed table, for example in tests.
+///
+/// # Examples
+///
+/// ```rust
+/// use url::{MemoryResolver, Url};
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut resolver = MemoryResolver::new();
+/// resolver.insert("example.net", ["192.0.2.1".parse()?]);
+///
+/// let url = Url::parse("https://example.net/")?;
+/// assert_eq!(url.resolve_with(&resolver, || None)?, ["192.0.2.1:443".parse()?]);
+///
+/// let url = Url::parse("https://example.com/")?;
+/// assert!(url.resolve_with(&resolver, || None).is_err());
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryResolver {
+    hosts: BTreeMap<String, Vec<IpAddr>>,
+}
+
+impl MemoryResolver {
+    /// Return a resolver that does not know any domain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add addresses for a domain, after those already known.
+    ///
+    /// The domain is matched ASCII case-insensitively.
+    /// Internationalized domains must be given in their ASCII (Punycode) form.
+    pub fn insert<I>(&mut self, domain: &str, addresses: I) -> &mut Self
+    where
+        I: IntoIterator<Item = IpAddr>,
+    {
+        self.hosts
+            .entry(domain.to_ascii_lowercase())
+            .or_default()
+            .extend(addresses);
+        self
+    }
+
+    /// Forget the addresses of a domain.
+    pub fn remove(&mut self, domain: &str) -> &mut Self {
+        self.hosts.remove(&domain.to_ascii_lowercase());
+        self
+    }
+
+    fn lookup(&self, domain: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        match self.hosts.get(&domain.to_ascii_lowercase()) {
+            Some(addresses) => Ok(addresses
+                .iter()
+                .map(|&address| SocketAddr::new(address, port))
+                .collect()),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, "Unknown host name")),
+        }
+    }
+}
+
+impl Resolver for MemoryResolver {
+    fn resolve(&self, domain: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        self.lookup(domain, port)
+    }
+}
+
+impl AsyncResolver for MemoryResolver {
+    type Future = future::Ready<io::Result<Vec<SocketAddr>>>;
+
+    fn resolve(&self, domain: &str, port: u16) -> Self::Future {
+        future::ready(self.lookup(domain, port))
+    }
+}
+
+/// A resolver using [`tokio::net::lookup_host`].
+///
+/// This type is only available if the `tokio` Cargo feature is enabled.
+/// Resolution needs a running Tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioResolver;
+
+#[cfg(feature = "tokio")]
+impl AsyncResolver for TokioResolver {
+    type Future = core::pin::Pin<
+        alloc::boxed::Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send + 'static>,
+    >;
+
+    fn resolve(&self, domain: &str, port: u16) -> Self::Future {
+        let domain = String::from(domain);
+        alloc::boxed::Box::pin(async move {
+            Ok(tokio::net::lookup_host((domain.as_str(), port))
+                .await?
+                .collect())
+        })
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{ParseError, Url};
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::ops::Deref;
+use core::{cmp, fmt, hash, str};
+
+/// A reference-counted `Url` that is cheap to clone.
+///
+/// Cloning a `Url` copies its serialization.
+/// Cloning an `ArcUrl` only increments a reference count,
+/// which is useful when the same URL is stored in many places,
+/// for example in every request, redirect record and log entry of an HTTP client.
+///
+/// `ArcUrl` dereferences to `Url`, so all getters and `Position` slicing are available.
+/// Mutation goes through [`ArcUrl::make_mut`], which copies the URL first
+/// if it is shared with other `ArcUrl` values.
+///
+/// Like `Url`, values compare and hash like their serialization.
+///
+/// # Examples
+///
+/// ```rust
+/// use url::{ArcUrl, Position};
+/// # use url::ParseError;
+///
+/// # fn run() -> Result<(), ParseError> {
+/// let url = ArcUrl::parse("https://example.net/a/b?c=d")?;
+/// let shared = url.clone();
+/// assert!(ArcUrl::ptr_eq(&url, &shared));
+/// assert_eq!(shared.host_str(), Some("example.net"));
+/// assert_eq!(&shared[Position::BeforePath..], "/a/b?c=d");
+///
+/// let mut modified = shared.clone();
+/// modified.make_mut().set_query(None);
+/// assert_eq!(modified.as_str(), "https://example.net/a/b");
+/// assert_eq!(shared.as_str(), "https://example.net/a/b?c=d");
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ArcUrl(Arc<Url>);
+
+impl ArcUrl {
+    /// Parse an absolute URL from a string.
+    ///
+    /// This is equivalent to `Url::parse(input).map(ArcUrl::from)`.
+    #[inline]
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Url::parse(input).map(Self::from)
+    }
+
+    /// Return a mutable reference to the URL,
+    /// copying it first if other `ArcUrl` values share it.
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut Url {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Return the inner `Url`, copying it if other `ArcUrl` values share it.
+    pub fn into_url(self) -> Url {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// Return whether two `ArcUrl` values share the same allocation.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+}
+
+impl Deref for ArcUrl {
+    type Target = Url;
+
+    #[inline]
+    fn deref(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl From<Url> for ArcUrl {
+    #[inline]
+    fn from(url: Url) -> Self {
+        Self(Arc::new(url))
+    }
+}
+
+impl From<Arc<Url>> for ArcUrl {
+    #[inline]
+    fn from(url: Arc<Url>) -> Self {
+        Self(url)
+    }
+}
+
+impl From<ArcUrl> for Arc<Url> {
+    #[inline]
+    fn from(url: ArcUrl) -> Self {
+        url.0
+    }
+}
+
+impl From<ArcUrl> for Url {
+    #[inline]
+    fn from(url: ArcUrl) -> Self {
+        url.into_url()
+    }
+}
+
+/// Parse a string as an URL, without a base URL or encoding override.
+impl str::FromStr for ArcUrl {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Self::parse(input)
+    }
+}
+
+/// Display the serialization of this URL.
+impl fmt::Display for ArcUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, formatter)
+    }
+}
+
+/// Debug the serialization of this URL.
+impl fmt::Debug for ArcUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, formatter)
+    }
+}
+
+/// URLs compare like their serialization.
+impl Eq for ArcUrl {}
+
+/// URLs compare like their serialization.
+impl PartialEq for ArcUrl {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<Url> for ArcUrl {
+    #[inline]
+    fn eq(&self, other: &Url) -> bool {
+        *self.0 == *other
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<ArcUrl> for Url {
+    #[inline]
+    fn eq(&self, other: &ArcUrl) -> bool {
+        *self == *other.0
+    }
+}
+
+/// URLs compare like their serialization.
+impl Ord for ArcUrl {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialOrd for ArcUrl {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// URLs hash like their serialization.
+impl hash::Hash for ArcUrl {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(&*self.0, state)
+    }
+}
+
+/// `ArcUrl` hashes and compares like `Url`,
+/// so a `HashMap<ArcUrl, _>` can be queried with a `&Url`.
+impl Borrow<Url> for ArcUrl {
+    #[inline]
+    fn borrow(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl AsRef<Url> for ArcUrl {
+    #[inline]
+    fn as_ref(&self) -> &Url {
+        &self.0
+    }
+}
+
+/// Return the serialization of this URL.
+impl AsRef<str> for ArcUrl {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+/// Serializes this URL into a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ArcUrl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes this URL from a `serde` stream.
+///
+/// This implementation is only available if the `serde` Cargo feature is enabled.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ArcUrl {
+    fn deserialize<D>(deserializer: D) -> Result<ArcUrl, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <Url as serde::Deserialize>::deserialize(deserializer).map(ArcUrl::from)
+    }
+}
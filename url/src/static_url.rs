@@ -0,0 +1,244 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::host::HostInternal;
+use crate::net::{Ipv4Addr, Ipv6Addr};
+use crate::Url;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use core::{fmt, hash};
+
+/// A URL that was parsed at compile time.
+///
+/// Values of this type are created by the `url!` macro of the `url_macros` crate,
+/// which runs `Url::parse` during compilation, reports parse errors as compile errors,
+/// and expands to a constant expression:
+///
+/// ```ignore
+/// use url_macros::url;
+///
+/// static API: url::StaticUrl = url!("https://api.example.com/v1/");
+///
+/// let users = API.join("users")?;
+/// ```
+///
+/// `StaticUrl` dereferences to `Url`.
+/// The `Url` is built on first use from the pre-computed components,
+/// without parsing, and then reused for the lifetime of the `StaticUrl`.
+/// Since a `Url` owns its serialization, this allocates a `Url` and a copy of the
+/// serialization on the heap, which are freed when the `StaticUrl` is dropped.
+/// Store `StaticUrl` values in a `static` rather than a `const`
+/// so that this happens only once.
+///
+/// [`as_str`](StaticUrl::as_str), `Display`, comparisons and hashing
+/// use the `&'static str` serialization directly and never allocate.
+pub struct StaticUrl {
+    serialization: &'static str,
+    scheme_end: u32,
+    username_end: u32,
+    host_start: u32,
+    host_end: u32,
+    host: HostInternal,
+    port: Option<u16>,
+    path_start: u32,
+    query_start: Option<u32>,
+    fragment_start: Option<u32>,
+    url: AtomicPtr<Url>,
+}
+
+impl StaticUrl {
+    /// Implementation detail of the `url!` macro, not part of the public API.
+    ///
+    /// `host` is 0 for no host, 1 for a domain, 2 for an IPv4 address in the first
+    /// four bytes of `address`, 3 for an IPv6 address and 4 for an opaque host.
+    #[doc(hidden)]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn __from_parts(
+        serialization: &'static str,
+        scheme_end: u32,
+        username_end: u32,
+        host_start: u32,
+        host_end: u32,
+        host: u8,
+        address: [u8; 16],
+        port: Option<u16>,
+        path_start: u32,
+        query_start: Option<u32>,
+        fragment_start: Option<u32>,
+    ) -> Self {
+        const fn segment(a: &[u8; 16], i: usize) -> u16 {
+            (a[2 * i] as u16) << 8 | a[2 * i + 1] as u16
+        }
+        let a = &address;
+        let host = match host {
+            0 => HostInternal::None,
+            1 => HostInternal::Domain,
+            2 => HostInternal::Ipv4(Ipv4Addr::new(a[0], a[1], a[2], a[3])),
+            4 => HostInternal::Opaque,
+            _ => HostInternal::Ipv6(Ipv6Addr::new(
+                segment(a, 0),
+                segment(a, 1),
+                segment(a, 2),
+                segment(a, 3),
+                segment(a, 4),
+                segment(a, 5),
+                segment(a, 6),
+                segment(a, 7),
+            )),
+        };
+        StaticUrl {
+            serialization,
+            scheme_end,
+            username_end,
+            host_start,
+            host_end,
+            host,
+            port,
+            path_start,
+            query_start,
+            fragment_start,
+            url: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Return the serialization of this URL.
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        self.serialization
+    }
+
+    /// Return a new `Url` with the components of this one, without parsing.
+    pub fn to_url(&self) -> Url {
+        Url {
+            serialization: self.serialization.to_owned(),
+            scheme_end: self.scheme_end,
+            username_end: self.username_end,
+            host_start: self.host_start,
+            host_end: self.host_end,
+            host: self.host,
+            port: self.port,
+            path_start: self.path_start,
+            query_start: self.query_start,
+            fragment_start: self.fragment_start,
+        }
+    }
+}
+
+impl Deref for StaticUrl {
+    type Target = Url;
+
+    fn deref(&self) -> &Url {
+        let mut url = self.url.load(Ordering::Acquire);
+        if url.is_null() {
+            let new = Box::into_raw(Box::new(self.to_url()));
+            match self
+                .url
+                .compare_exchange(url, new, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => url = new,
+                Err(existing) => {
+                    // Another thread won the race, use its `Url` instead.
+                    // SAFETY: `new` was created by `Box::into_raw` above and never shared.
+                    drop(unsafe { Box::from_raw(new) });
+                    url = existing;
+                }
+            }
+        }
+        // SAFETY: a non-null pointer was created by `Box::into_raw` and is only freed in `drop`.
+        unsafe { &*url }
+    }
+}
+
+impl Drop for StaticUrl {
+    fn drop(&mut self) {
+        let url = *self.url.get_mut();
+        if !url.is_null() {
+            // SAFETY: a non-null pointer was created by `Box::into_raw` in `deref`.
+            drop(unsafe { Box::from_raw(url) });
+        }
+    }
+}
+
+impl From<&StaticUrl> for Url {
+    #[inline]
+    fn from(url: &StaticUrl) -> Self {
+        url.to_url()
+    }
+}
+
+impl From<StaticUrl> for Url {
+    #[inline]
+    fn from(url: StaticUrl) -> Self {
+        url.to_url()
+    }
+}
+
+/// Display the serialization of this URL.
+impl fmt::Display for StaticUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.serialization, formatter)
+    }
+}
+
+/// Debug the serialization of this URL.
+impl fmt::Debug for StaticUrl {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, formatter)
+    }
+}
+
+/// URLs compare like their serialization.
+impl Eq for StaticUrl {}
+
+/// URLs compare like their serialization.
+impl PartialEq for StaticUrl {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.serialization == other.serialization
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<Url> for StaticUrl {
+    #[inline]
+    fn eq(&self, other: &Url) -> bool {
+        self.serialization == other.as_str()
+    }
+}
+
+/// URLs compare like their serialization.
+impl PartialEq<StaticUrl> for Url {
+    #[inline]
+    fn eq(&self, other: &StaticUrl) -> bool {
+        self.as_str() == other.serialization
+    }
+}
+
+/// URLs hash like their serialization.
+impl hash::Hash for StaticUrl {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(self.serialization, state)
+    }
+}
+
+/// Return the serialization of this URL.
+impl AsRef<str> for StaticUrl {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.serialization
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! [WTF-8](https://simonsapin.github.io/wtf-8/) conversions for Windows file paths.
+//!
+//! Windows file names are sequences of 16-bit code units that are not required
+//! to be well-formed UTF-16. WTF-8 extends UTF-8 so that unpaired surrogates
+//! can be represented as well, which lets `file:` URLs percent-encode
+//! any Windows path losslessly.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::str;
+use std::ffi::{OsStr, OsString};
+
+/// Return the WTF-8 bytes of an `OsStr`.
+///
+/// On non-Windows platforms only valid Unicode is supported.
+pub(crate) fn os_str_bytes(os_str: &OsStr) -> Result<Cow<'_, [u8]>, ()> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        if let Some(s) = os_str.to_str() {
+            return Ok(s.as_bytes().into());
+        }
+        let mut bytes = Vec::with_capacity(os_str.len());
+        encode_wide(os_str.encode_wide(), &mut bytes);
+        Ok(bytes.into())
+    }
+    #[cfg(not(windows))]
+    {
+        os_str.to_str().map(|s| s.as_bytes().into()).ok_or(())
+    }
+}
+
+/// Convert WTF-8 bytes to an `OsString`.
+///
+/// On non-Windows platforms only valid UTF-8 is supported.
+pub(crate) fn to_os_string(bytes: Vec<u8>) -> Result<OsString, ()> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStringExt;
+        match alloc::string::String::from_utf8(bytes) {
+            Ok(string) => Ok(string.into()),
+            Err(err) => Ok(OsString::from_wide(&decode_wide(err.as_bytes())?)),
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        alloc::string::String::from_utf8(bytes)
+            .map(OsString::from)
+            .map_err(|_| ())
+    }
+}
+
+/// Encode potentially ill-formed UTF-16 as WTF-8.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn encode_wide<I: IntoIterator<Item = u16>>(units: I, out: &mut Vec<u8>) {
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            Err(err) => {
+                let surrogate = err.unpaired_surrogate();
+                out.extend_from_slice(&[
+                    0xE0 | (surrogate >> 12) as u8,
+                    0x80 | ((surrogate >> 6) & 0x3F) as u8,
+                    0x80 | (surrogate & 0x3F) as u8,
+                ]);
+            }
+        }
+    }
+}
+
+/// Decode WTF-8 to potentially ill-formed UTF-16.
+///
+/// Return `Err` if the input is not well-formed WTF-8. In particular, a lead
+/// surrogate immediately followed by a trail surrogate is rejected:
+/// that pair must be encoded as a single four-byte sequence instead.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn decode_wide(mut bytes: &[u8]) -> Result<Vec<u16>, ()> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut after_lead_surrogate = false;
+    loop {
+        let (valid, rest) = match str::from_utf8(bytes) {
+            Ok(s) => (s, &b""[..]),
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                (str::from_utf8(valid).unwrap(), rest)
+            }
+        };
+        if !valid.is_empty() {
+            after_lead_surrogate = false;
+            units.extend(valid.encode_utf16());
+        }
+        match *rest {
+            [] => return Ok(units),
+            [0xED, second @ 0xA0..=0xBF, third @ 0x80..=0xBF, ..] => {
+                let surrogate = 0xD000 | (u16::from(second & 0x3F) << 6) | u16::from(third & 0x3F);
+                let is_lead = surrogate < 0xDC00;
+                if after_lead_surrogate && !is_lead {
+                    return Err(());
+                }
+                after_lead_surrogate = is_lead;
+                units.push(surrogate);
+                bytes = &rest[3..];
+            }
+            _ => return Err(()),
+        }
+    }
+}
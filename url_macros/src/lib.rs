@@ -0,0 +1,206 @@
+// Copyright 2026 The rust-url developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compile-time URL literals for the [`url`](https://docs.rs/url) crate.
+//!
+//! The [`url!`] macro parses a string literal with `url::Url::parse` during compilation.
+//! Parse errors are reported as compile errors,
+//! and valid URLs expand to a constant `url::StaticUrl` which dereferences to `url::Url`
+//! without any parsing at run time.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use url::StaticUrl;
+//! use url_macros::url;
+//!
+//! static API: StaticUrl = url!("https://api.example.com/v1/");
+//!
+//! # fn run() -> Result<(), url::ParseError> {
+//! assert_eq!(API.host_str(), Some("api.example.com"));
+//! let users = API.join("users")?;
+//! assert_eq!(users.as_str(), "https://api.example.com/v1/users");
+//! # Ok(())
+//! # }
+//! # run().unwrap();
+//! ```
+//!
+//! Invalid URLs do not compile:
+//!
+//! ```compile_fail
+//! use url_macros::url;
+//!
+//! static API: url::StaticUrl = url!("https://exa mple.com/");
+//! ```
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+use url::{Host, Position, Url};
+
+/// Parse a URL at compile time.
+///
+/// The argument must be a single string literal, which may be a raw string.
+/// It is parsed with `url::Url::parse`, and the `ParseError` message is emitted as a
+/// compile error on failure.
+/// On success the macro expands to a constant expression of type `url::StaticUrl`,
+/// so the calling crate must also depend on `url`.
+///
+/// ```rust
+/// use url_macros::url;
+///
+/// static LOCALHOST: url::StaticUrl = url!("http://[::1]:8080/status?verbose#top");
+///
+/// assert_eq!(LOCALHOST.port(), Some(8080));
+/// assert_eq!(LOCALHOST.query(), Some("verbose"));
+/// assert_eq!(*LOCALHOST, url::Url::parse(LOCALHOST.as_str()).unwrap());
+/// ```
+#[proc_macro]
+pub fn url(input: TokenStream) -> TokenStream {
+    let (input, span) = match string_literal(input) {
+        Ok(literal) => literal,
+        Err((message, span)) => return compile_error(message, span),
+    };
+    match Url::parse(&input) {
+        Ok(url) => expand(&url),
+        Err(error) => compile_error(&format!("invalid URL: {}", error), span),
+    }
+}
+
+/// Return the value of the single string literal in `input`, and its span.
+fn string_literal(input: TokenStream) -> Result<(String, Span), (&'static str, Span)> {
+    const EXPECTED: &str = "expected a single string literal";
+    let mut tokens = input.into_iter();
+    let token = match (tokens.next(), tokens.next()) {
+        (Some(token), None) => token,
+        (Some(_), Some(extra)) => return Err((EXPECTED, extra.span())),
+        (None, _) => return Err((EXPECTED, Span::call_site())),
+    };
+    match token {
+        // Arguments forwarded by `macro_rules!` macros arrive in an invisible group.
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            string_literal(group.stream())
+        }
+        TokenTree::Literal(literal) => {
+            let span = literal.span();
+            unquote(&literal.to_string())
+                .map(|value| (value, span))
+                .ok_or((EXPECTED, span))
+        }
+        token => Err((EXPECTED, token.span())),
+    }
+}
+
+/// Return the value of a string literal token, or `None` if it is another kind of literal.
+fn unquote(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let body = body.strip_suffix(&raw[..hashes])?.strip_suffix('"')?;
+        return Some(body.to_owned());
+    }
+    let body = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '\'' => value.push('\''),
+            '"' => value.push('"'),
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                value.push(u8::from_str_radix(&digits, 16).ok()? as char);
+            }
+            'u' => {
+                chars.next()?;
+                let digits: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let digits = digits.replace('_', "");
+                value.push(char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?);
+            }
+            '\n' => {
+                while chars.peek().map_or(false, |c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// Return the tokens of a `url::StaticUrl::__from_parts` call that rebuilds `url`.
+fn expand(url: &Url) -> TokenStream {
+    // The offsets of the components in the serialization, as stored in `Url`.
+    let offset = |position| url[..position].len();
+    let query_start = url.query().map(|_| offset(Position::AfterPath));
+    let fragment_start = url.fragment().map(|_| offset(Position::AfterQuery));
+    let (host, address) = match url.host() {
+        None => (0, [0; 16]),
+        Some(Host::Domain(_)) if url.opaque_host().is_some() => (4, [0; 16]),
+        Some(Host::Domain(_)) => (1, [0; 16]),
+        Some(Host::Ipv4(address)) => {
+            let mut bytes = [0; 16];
+            bytes[..4].copy_from_slice(&address.octets());
+            (2, bytes)
+        }
+        Some(Host::Ipv6(address)) => (3, address.octets()),
+    };
+    format!(
+        "::url::StaticUrl::__from_parts({}, {}, {}, {}, {}, {}, {:?}, {}, {}, {}, {})",
+        Literal::string(url.as_str()),
+        offset(Position::AfterScheme),
+        offset(Position::AfterUsername),
+        offset(Position::BeforeHost),
+        offset(Position::AfterHost),
+        host,
+        address,
+        option(url.port()),
+        offset(Position::BeforePath),
+        option(query_start),
+        option(fragment_start),
+    )
+    .parse()
+    .unwrap()
+}
+
+fn option<T: std::fmt::Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => format!("::core::option::Option::Some({})", value),
+        None => "::core::option::Option::None".to_owned(),
+    }
+}
+
+/// Return a `compile_error!` invocation reporting `message` at `span`.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let tokens: TokenStream = format!("::core::compile_error!({})", Literal::string(message))
+        .parse()
+        .unwrap();
+    tokens
+        .into_iter()
+        .map(|token| respan(token, span))
+        .collect()
+}
+
+fn respan(mut token: TokenTree, span: Span) -> TokenTree {
+    if let TokenTree::Group(group) = &token {
+        let stream = group
+            .stream()
+            .into_iter()
+            .map(|token| respan(token, span))
+            .collect();
+        token = TokenTree::Group(Group::new(group.delimiter(), stream));
+    }
+    token.set_span(span);
+    token
+}